@@ -1,14 +1,66 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/chat.rs
+// Global chat.
+//
+// Messages are ChatMessage entries linked from hourly "chat.YYYY-MM-DD.HH" anchors, so
+// agents who were offline can read the history back. Agents currently online (see
+// presence.rs) also get each message immediately as a Signal::GlobalChatMessage.
+//
+// In-game chat goes only to the game's participants (see `send_game_chat_message`)
+// and is optionally persisted as a ChatMessage linked from the game hash.
 use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::chat_message::{chat_bucket_name, hour_of};
 use crate::{Signal, ChatMessagePayload}; // Assuming ChatMessagePayload is in lib.rs or imported there
-use crate::player::get_all_player_pubkeys; // Import the new function
+use crate::presence::online_agents;
+use crate::utils::anchor_for;
+use crate::signals::{broadcast_to_opponents, send_signal_to, DeliveryFailure};
+use crate::game::get_latest_game_state;
+use ping_2_pong_integrity::chat_message_validation::MAX_CHAT_MESSAGE_LENGTH;
+
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+const MAX_HISTORY_LIMIT: usize = 200;
+const MAX_HISTORY_HOURS: i64 = 72; // How far back get_chat_history looks without a `since`
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetChatHistoryInput {
+    pub since: Option<Timestamp>, // Only messages sent after this time
+    pub limit: Option<u32>,       // Defaults to 50, capped at 200
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GameChatMessageInput {
+    pub game_id: ActionHash, // Original Game create action
+    pub content: String,
+    pub persist: bool,       // Also store the message, linked from the game hash
+}
+
+/// The stored message and the online agents live delivery failed for.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChatDeliveryReport {
+    pub record: Record,
+    pub delivered: u32,
+    pub failed: Vec<DeliveryFailure>,
+}
 
 #[hdk_extern]
-pub fn send_global_chat_message(content: String) -> ExternResult<()> {
+pub fn send_global_chat_message(content: String) -> ExternResult<ChatDeliveryReport> {
     let my_agent_info = agent_info()?;
     let my_pub_key = my_agent_info.agent_latest_pubkey.clone();
     let now_timestamp = sys_time()?;
 
+    // 1. Persist the message and link it from its hourly bucket.
+    // Length and rate limits are enforced by integrity validation.
+    let message = ChatMessage {
+        sender: my_pub_key.clone(),
+        content: content.clone(),
+        timestamp: now_timestamp,
+    };
+    let message_hash = create_entry(&EntryTypes::ChatMessage(message))?;
+    let bucket_hash = anchor_for(&chat_bucket_name(hour_of(now_timestamp)))?;
+    create_link(bucket_hash, message_hash.clone(), LinkTypes::ChatBucketToMessages, ())?;
+
     let payload = ChatMessagePayload {
+        action_hash: message_hash.clone(),
         timestamp: now_timestamp,
         sender: my_pub_key.clone(),
         content,
@@ -16,30 +68,167 @@ pub fn send_global_chat_message(content: String) -> ExternResult<()> {
 
     let signal = Signal::GlobalChatMessage(payload);
 
-    // 1. Emit locally for sender's UI
+    // 2. Emit locally for sender's UI
     emit_signal(&signal)?;
 
-    // 2. Get all player pubkeys
-    let all_player_pubkeys = get_all_player_pubkeys(())?; // Call the new function
-
-    // 3. Send to all other players via call_remote
-    for target_agent_key in all_player_pubkeys {
-        if target_agent_key != my_pub_key { // Don't send to self again via call_remote
-            let _ = call_remote(
-                target_agent_key.clone(),
-                "ping_2_pong", // Zome name
-                "receive_remote_signal".into(),
-                None, // Unrestricted cap grant assumed for receive_remote_signal
-                signal.clone() // Clone signal for each call
-            );
-            // Error handling for call_remote can be added if necessary,
-            // but often chat messages are fire-and-forget.
-            // Example logging if needed:
-            // match result {
-            //     Ok(_) => debug!("Successfully sent remote signal to {:?}", target_agent_key),
-            //     Err(e) => warn!("Failed to send remote signal to {:?}: {:?}", target_agent_key, e),
-            // }
+    // 3. Deliver live to the agents currently online
+    let recipients = online_agents()?;
+    let recipient_count = recipients.len() as u32;
+    let failed = send_signal_to(recipients, &signal)?;
+    for failure in &failed {
+        debug!("[chat.rs] Live delivery to {:?} failed: {}", failure.agent, failure.reason);
+    }
+
+    let record = get(message_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created ChatMessage".to_string())))?;
+    Ok(ChatDeliveryReport { record, delivered: recipient_count - failed.len() as u32, failed })
+}
+
+/// Deletes one of our own chat messages (integrity rejects deletes by anyone else)
+/// and tells online players to drop it from their view.
+#[hdk_extern]
+pub fn delete_chat_message(message_hash: ActionHash) -> ExternResult<ActionHash> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let record = get(message_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Chat message not found".into())))?;
+    let message = record
+        .entry()
+        .to_app_option::<ChatMessage>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record is not a ChatMessage".into())))?;
+    if message.sender != my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the sender can delete a chat message".into())));
+    }
+
+    // Remove the bucket link so history queries skip it, then the entry itself
+    let bucket_hash = anchor_for(&chat_bucket_name(hour_of(message.timestamp)))?;
+    let links = get_links(GetLinksInputBuilder::try_new(bucket_hash, LinkTypes::ChatBucketToMessages)?.build())?;
+    for link in links {
+        if link.target.clone().into_action_hash().as_ref() == Some(&message_hash) {
+            delete_link(link.create_link_hash)?;
         }
     }
-    Ok(())
+    let delete_hash = delete_entry(message_hash.clone())?;
+
+    let signal = Signal::GlobalChatMessageDeleted { action_hash: message_hash };
+    emit_signal(&signal)?;
+    // Agents who miss this will not see the message in their next history load
+    let failed = send_signal_to(online_agents()?, &signal)?;
+    if !failed.is_empty() {
+        debug!("[chat.rs] Deletion of chat message not delivered to {} agents", failed.len());
+    }
+    Ok(delete_hash)
+}
+
+/// The most recent chat messages sent after `since`, oldest first.
+/// Walks the hourly buckets backwards from now until `limit` messages are found,
+/// `since` is reached, or 72 hours have been scanned.
+#[hdk_extern]
+pub fn get_chat_history(input: GetChatHistoryInput) -> ExternResult<Vec<Record>> {
+    let limit = input.limit.map(|l| l as usize).unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+    let current_hour = hour_of(sys_time()?);
+    let first_hour = match input.since {
+        Some(since) => hour_of(since).max(current_hour - MAX_HISTORY_HOURS),
+        None => current_hour - MAX_HISTORY_HOURS,
+    };
+
+    let mut links: Vec<Link> = Vec::new();
+    let mut hour = current_hour;
+    while hour >= first_hour && links.len() < limit {
+        let bucket_hash = Path::from(chat_bucket_name(hour)).path_entry_hash()?;
+        let mut bucket_links = get_links(GetLinksInputBuilder::try_new(bucket_hash, LinkTypes::ChatBucketToMessages)?.build())?;
+        if let Some(since) = input.since {
+            bucket_links.retain(|link| link.timestamp > since);
+        }
+        links.extend(bucket_links);
+        hour -= 1;
+    }
+
+    // Keep the newest `limit` links (link timestamps match message creation)
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    links.truncate(limit);
+
+    let get_inputs: Vec<GetInput> = links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .map(|ah| GetInput::new(ah.into(), GetOptions::default()))
+        .collect();
+    if get_inputs.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut records: Vec<Record> = HDK.with(|hdk| hdk.borrow().get(get_inputs))?
+        .into_iter()
+        .flatten()
+        .collect();
+    records.sort_by_key(|record| record.action().timestamp());
+    Ok(records)
+}
+
+/// Sends a chat message to the other participant of a game. Only the game's players
+/// may use the channel; with `persist` the message is also stored and linked from the
+/// game so it shows up in `get_game_chat_history`.
+#[hdk_extern]
+pub fn send_game_chat_message(input: GameChatMessageInput) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let (_, game) = get_latest_game_state(&input.game_id)?;
+    if game.player_1 != my_pub_key && game.player_2.as_ref() != Some(&my_pub_key) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the game's players can use its chat".into())));
+    }
+    if input.content.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Chat message cannot be empty".into())));
+    }
+    if input.content.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Chat message is too long (max {} characters)", MAX_CHAT_MESSAGE_LENGTH))));
+    }
+
+    let now_timestamp = sys_time()?;
+    let action_hash = if input.persist {
+        let message = ChatMessage {
+            sender: my_pub_key.clone(),
+            content: input.content.clone(),
+            timestamp: now_timestamp,
+        };
+        let message_hash = create_entry(&EntryTypes::ChatMessage(message))?;
+        create_link(input.game_id.clone(), message_hash.clone(), LinkTypes::GameToChatMessages, ())?;
+        Some(message_hash)
+    } else {
+        None
+    };
+
+    let signal = Signal::GameChatMessage {
+        game_id: input.game_id.clone(),
+        sender: my_pub_key,
+        content: input.content,
+        timestamp: now_timestamp,
+        action_hash,
+    };
+    emit_signal(&signal)?;
+    broadcast_to_opponents(&input.game_id, &signal)
+}
+
+/// Persisted chat messages of a game, oldest first. Like the live channel, the history
+/// is only served to the game's players; the entries themselves are public DHT data,
+/// which the UI says when a message is saved.
+#[hdk_extern]
+pub fn get_game_chat_history(game_id: ActionHash) -> ExternResult<Vec<Record>> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let (_, game) = get_latest_game_state(&game_id)?;
+    if game.player_1 != my_pub_key && game.player_2.as_ref() != Some(&my_pub_key) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the game's players can read its chat".into())));
+    }
+    let links = get_links(GetLinksInputBuilder::try_new(game_id, LinkTypes::GameToChatMessages)?.build())?;
+    let get_inputs: Vec<GetInput> = links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .map(|ah| GetInput::new(ah.into(), GetOptions::default()))
+        .collect();
+    if get_inputs.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut records: Vec<Record> = HDK.with(|hdk| hdk.borrow().get(get_inputs))?
+        .into_iter()
+        .flatten()
+        .collect();
+    records.sort_by_key(|record| record.action().timestamp());
+    Ok(records)
 }
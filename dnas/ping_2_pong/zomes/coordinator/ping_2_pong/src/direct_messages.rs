@@ -0,0 +1,144 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/direct_messages.rs
+// Direct messages between players.
+//
+// A message is first delivered live: `send_direct_message` calls the recipient's
+// `receive_direct_message`, which emits a Signal::DirectMessage and answers with a
+// delivery receipt. If the recipient cannot be reached, the message is stored as a
+// DirectMessage entry linked from the recipient's key (their offline inbox).
+// When the recipient acknowledges an inbox message, the sender gets a
+// Signal::DirectMessageDelivered.
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::chat_message_validation::MAX_CHAT_MESSAGE_LENGTH;
+use crate::Signal;
+use crate::signals::{send_signal_to, zome_name};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SendDirectMessageInput {
+    pub to: AgentPubKey,
+    pub content: String,
+}
+
+/// What the sender sends to the recipient's `receive_direct_message`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirectMessagePayload {
+    pub content: String,
+    pub timestamp: Timestamp,
+}
+
+/// Returned by the recipient when a live message reached them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeliveryReceipt {
+    pub recipient: AgentPubKey,
+    pub received_at: Timestamp,
+}
+
+/// Outcome of `send_direct_message`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum DirectMessageStatus {
+    Delivered { receipt: DeliveryReceipt }, // The recipient was online
+    Stored { action_hash: ActionHash },     // Left in the recipient's inbox
+}
+
+/// Sends a private message to another player. Returns a delivery receipt when they
+/// were online, otherwise the hash of the message stored in their inbox.
+#[hdk_extern]
+pub fn send_direct_message(input: SendDirectMessageInput) -> ExternResult<DirectMessageStatus> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if input.to == my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot send a direct message to yourself".into())));
+    }
+    if input.content.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Direct message cannot be empty".into())));
+    }
+    if input.content.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Direct message is too long (max {} characters)", MAX_CHAT_MESSAGE_LENGTH))));
+    }
+
+    let payload = DirectMessagePayload { content: input.content.clone(), timestamp: sys_time()? };
+
+    // 1. Try live delivery
+    match call_remote(input.to.clone(), zome_name()?, "receive_direct_message".into(), None, payload.clone()) {
+        Ok(ZomeCallResponse::Ok(output)) => {
+            let receipt = output
+                .decode::<DeliveryReceipt>()
+                .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+            return Ok(DirectMessageStatus::Delivered { receipt });
+        }
+        Ok(other) => debug!("[direct_messages] Live delivery to {:?} failed: {:?}", input.to, other),
+        Err(e) => debug!("[direct_messages] Live delivery to {:?} failed: {:?}", input.to, e),
+    }
+
+    // 2. Recipient is offline: leave the message in their inbox
+    let message = DirectMessage {
+        sender: my_pub_key,
+        recipient: input.to.clone(),
+        content: payload.content,
+        timestamp: payload.timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::DirectMessage(message))?;
+    create_link(input.to, action_hash.clone(), LinkTypes::RecipientToDirectMessages, ())?;
+    Ok(DirectMessageStatus::Stored { action_hash })
+}
+
+/// Called remotely by the sender. Shows the message in our UI and returns a receipt.
+#[hdk_extern]
+pub fn receive_direct_message(payload: DirectMessagePayload) -> ExternResult<DeliveryReceipt> {
+    let sender = call_info()?.provenance;
+    if payload.content.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest("Direct message is too long".into())));
+    }
+    emit_signal(&Signal::DirectMessage {
+        sender,
+        content: payload.content,
+        timestamp: payload.timestamp,
+    })?;
+    Ok(DeliveryReceipt {
+        recipient: agent_info()?.agent_latest_pubkey,
+        received_at: sys_time()?,
+    })
+}
+
+/// Messages left in our inbox while we were offline, oldest first.
+#[hdk_extern]
+pub fn get_inbox(_: ()) -> ExternResult<Vec<Record>> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let links = get_links(GetLinksInputBuilder::try_new(my_pub_key, LinkTypes::RecipientToDirectMessages)?.build())?;
+    let get_inputs: Vec<GetInput> = links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .map(|ah| GetInput::new(ah.into(), GetOptions::default()))
+        .collect();
+    if get_inputs.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut records: Vec<Record> = HDK.with(|hdk| hdk.borrow().get(get_inputs))?
+        .into_iter()
+        .flatten()
+        .collect();
+    records.sort_by_key(|record| record.action().timestamp());
+    Ok(records)
+}
+
+/// Removes a message from our inbox once read and sends the sender a delivery receipt.
+#[hdk_extern]
+pub fn acknowledge_direct_message(action_hash: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let links = get_links(GetLinksInputBuilder::try_new(my_pub_key.clone(), LinkTypes::RecipientToDirectMessages)?.build())?;
+    let Some(link) = links.into_iter().find(|link| link.target.clone().into_action_hash().as_ref() == Some(&action_hash)) else {
+        return Ok(()); // Already acknowledged
+    };
+    delete_link(link.create_link_hash)?;
+
+    // Best effort receipt; the sender may be offline
+    let signal = Signal::DirectMessageDelivered {
+        inbox_hash: action_hash,
+        recipient: my_pub_key,
+        received_at: sys_time()?,
+    };
+    for failure in send_signal_to(vec![link.author], &signal)? {
+        debug!("[direct_messages] Delivery receipt not sent to {:?}: {}", failure.agent, failure.reason);
+    }
+    Ok(())
+}
@@ -1,15 +1,17 @@
 use hdk::prelude::*;
+use std::collections::HashSet;
 use ping_2_pong_integrity::*;
 // Use GameStatus directly from integrity crate
-use ping_2_pong_integrity::game::GameStatus;
+use ping_2_pong_integrity::game::{GameStatus, GameVisibility, GameStatusIndexTag, status_index_path};
 // Import helpers from local utils module
-use crate::utils::{ player_exists, is_player_in_ongoing_game, anchor_for };
+use crate::utils::{ player_exists, is_player_in_ongoing_game, anchor_for, day_of, game_bucket_anchor };
 // Import Signal enum definition from local lib.rs
 use crate::Signal;
 
 // --- Extern Functions ---
 
 /// Fetches all game records linked from the global "games" anchor.
+/// Returns the original create records of every game ever created; lobbies should use `list_games`.
 #[hdk_extern]
 pub fn get_all_games(_: ()) -> ExternResult<Vec<Record>> {
     let games_anchor = anchor_for("games")?;
@@ -43,6 +45,88 @@ pub fn get_all_games(_: ()) -> ExternResult<Vec<Record>> {
 }
 
 
+const DEFAULT_LIST_GAMES_LIMIT: u32 = 20;
+const MAX_LIST_GAMES_LIMIT: u32 = 100;
+const MAX_LIST_GAMES_DAYS: i64 = 30; // Daily buckets scanned per list_games call
+
+/// Input structure for the `list_games` function. All filters are optional.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListGamesInput {
+    pub status: Option<GameStatus>,    // Only games currently in this status
+    pub player: Option<AgentPubKey>,   // Only games this agent plays in
+    pub before: Option<Timestamp>,     // Only games created before this time (pagination cursor)
+    pub limit: Option<u32>,            // Page size (default 20, max 100)
+}
+
+// Latest state of a game for listing, filtered by status and creation time.
+fn latest_listed_game(original_game_hash: ActionHash, input: &ListGamesInput, before: Timestamp) -> ExternResult<Option<(Timestamp, Record)>> {
+    let Some(record) = get_latest_game(original_game_hash)? else {
+        return Ok(None);
+    };
+    let Ok(Some(game)) = record.entry().to_app_option::<Game>() else {
+        return Ok(None);
+    };
+    if game.created_at >= before {
+        return Ok(None);
+    }
+    if input.status.as_ref().is_some_and(|status| *status != game.game_status) {
+        return Ok(None);
+    }
+    if input.player.as_ref().is_some_and(|player| *player != game.player_1 && Some(player) != game.player_2.as_ref()) {
+        return Ok(None);
+    }
+    Ok(Some((game.created_at, record)))
+}
+
+/// Lists the latest state of games, newest first.
+/// Without a `player` filter, games are read from the daily bucket anchors, walking
+/// back from `before` (or now) for at most 30 days, so the full history is never scanned.
+/// To fetch the next page, pass the `created_at` of the last game returned as `before`.
+#[hdk_extern]
+pub fn list_games(input: ListGamesInput) -> ExternResult<Vec<Record>> {
+    let limit = input.limit.unwrap_or(DEFAULT_LIST_GAMES_LIMIT).clamp(1, MAX_LIST_GAMES_LIMIT) as usize;
+    let before = match input.before {
+        Some(before) => before,
+        None => Timestamp::from_micros(sys_time()?.as_micros() + 1), // Include games created right now
+    };
+
+    let mut games: Vec<(Timestamp, Record)> = Vec::new();
+    if let Some(player) = &input.player {
+        // A player's own games are found through their Player1/Player2 links
+        let mut links = get_links(GetLinksInputBuilder::try_new(player.clone(), LinkTypes::Player1ToGames)?.build())?;
+        links.extend(get_links(GetLinksInputBuilder::try_new(player.clone(), LinkTypes::Player2ToGames)?.build())?);
+        let mut game_hashes: Vec<ActionHash> = links.into_iter().filter_map(|link| link.target.into_action_hash()).collect();
+        game_hashes.sort();
+        game_hashes.dedup();
+        for game_hash in game_hashes {
+            if let Some(listed) = latest_listed_game(game_hash, &input, before)? {
+                games.push(listed);
+            }
+        }
+    } else {
+        let last_day = day_of(before);
+        for day in (last_day - MAX_LIST_GAMES_DAYS + 1..=last_day).rev() {
+            let mut links = get_links(GetLinksInputBuilder::try_new(game_bucket_anchor(day)?, LinkTypes::GameBucketToGames)?.build())?;
+            links.sort_by_key(|link| std::cmp::Reverse(link.timestamp)); // Newest first within the day
+            for link in links {
+                let Some(game_hash) = link.target.into_action_hash() else { continue };
+                if let Some(listed) = latest_listed_game(game_hash, &input, before)? {
+                    games.push(listed);
+                }
+            }
+            // Days are visited newest first, so a full page can stop the walk
+            if games.len() >= limit {
+                break;
+            }
+        }
+    }
+
+    games.sort_by_key(|(created_at, _)| std::cmp::Reverse(*created_at));
+    games.truncate(limit);
+    Ok(games.into_iter().map(|(_, record)| record).collect())
+}
+
+
 /// Enum representing a player's status for the lobby.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PlayerStatus {
@@ -66,6 +150,8 @@ pub fn get_player_status(player_pub_key: AgentPubKey) -> ExternResult<PlayerStat
 pub struct CreateGameInput {
     pub player_1: AgentPubKey,
     pub player_2: Option<AgentPubKey>, // Optional: Used for direct invitations
+    #[serde(default)]
+    pub visibility: GameVisibility,    // InviteOnly requires player_2
 }
 
 
@@ -99,6 +185,10 @@ pub fn join_game(original_game_hash: ActionHash) -> ExternResult<Record> {
             "Cannot join game: Game status is not 'Waiting', it's {:?}", current_game.game_status
         ))));
     }
+    // Invite-only games are reserved for the invited Player 2
+    if current_game.visibility == GameVisibility::InviteOnly && current_game.player_2.as_ref() != Some(&caller_pubkey) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot join game: This game is invite-only".into())));
+    }
     // Check if Player 2 slot is already taken by someone else
     if current_game.player_2.is_some() && current_game.player_2.as_ref() != Some(&caller_pubkey) {
         return Err(wasm_error!(WasmErrorInner::Guest("Cannot join game: Player 2 slot is already taken by another player".into())));
@@ -130,6 +220,10 @@ pub fn join_game(original_game_hash: ActionHash) -> ExternResult<Record> {
         player_2_paddle: current_game.player_2_paddle,
         ball_x: current_game.ball_x,
         ball_y: current_game.ball_y,
+        result: None,                          // Result is only set when the game finishes
+        visibility: current_game.visibility,
+        tournament_id: current_game.tournament_id,
+        series_id: current_game.series_id,
     };
 
     // 4. Commit the update action to the DHT
@@ -156,6 +250,8 @@ pub fn join_game(original_game_hash: ActionHash) -> ExternResult<Record> {
         (),
     )?;
     debug!("[join_game] Created GameUpdates link from {:?} to {:?}", original_game_hash, update_action_hash);
+    // Move the game from the Waiting index to the InProgress index
+    reindex_game_status(&original_game_hash, &GameStatus::InProgress, &update_action_hash)?;
 
     // 7. *** Emit GameStarted signal (Broadcast) ***
     //    This signal informs connected UIs that the game is ready to start.
@@ -170,14 +266,10 @@ pub fn join_game(original_game_hash: ActionHash) -> ExternResult<Record> {
     // 7. Broadcast locally (player 2) …
     emit_signal(&start_sig)?;
 
-    // 8. Relay to player 1 – synchronous RPC
-    call_remote(
-        player1_pubkey.clone(),          // destination agent
-        zome_info()?.name,               // current zome name
-        "receive_remote_signal".into(),  // the helper you just added
-        None,                            // provenance (cap secret)
-        &start_sig                       // same payload
-    )?;
+    // 8. Relay to player 1 (player 1's UI also polls the game, so a miss is not fatal)
+    for failure in crate::signals::send_signal_to(vec![player1_pubkey.clone()], &start_sig)? {
+        warn!("[join_game] GameStarted not delivered to player 1: {}", failure.reason);
+    }
 
     debug!("[join_game] Emitted GameStarted signal (broadcast): {:?}", start_sig);
 
@@ -212,6 +304,9 @@ pub fn create_game(input: CreateGameInput) -> ExternResult<Record> {
     if is_player_in_ongoing_game(&input.player_1)? {
         return Err(wasm_error!(WasmErrorInner::Guest("Player 1 is already in an ongoing game".into())));
     }
+    if input.visibility == GameVisibility::InviteOnly && input.player_2.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest("An invite-only game needs the invited Player 2".into())));
+    }
     // If Player 2 is specified (for an invite), validate them too
     if let Some(player2) = &input.player_2 {
         if !player_exists(player2)? {
@@ -236,9 +331,25 @@ pub fn create_game(input: CreateGameInput) -> ExternResult<Record> {
         player_2_paddle: 250,
         ball_x: 400,
         ball_y: 300,
+        result: None,                     // Set by finish_game with both players' signatures
+        visibility: input.visibility.clone(),
+        tournament_id: None,              // Bracket games are created by tournament.rs
+        series_id: None,                  // Series games are created by match_series.rs
     };
     debug!("[create_game] Constructed game entry: {:?}", game);
 
+    let game_action_hash = commit_new_game(&game)?;
+
+    // Fetch and return the created record
+    let record = get(game_action_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created Game".to_string())))?;
+
+    Ok(record)
+}
+
+/// Creates a Game entry and the links every game needs (players, anchors, status index).
+/// Returns the original create action hash, which identifies the game.
+pub(crate) fn commit_new_game(game: &Game) -> ExternResult<ActionHash> {
     // Create the Game entry on the DHT
     let game_action_hash = create_entry(&EntryTypes::Game(game.clone()))?;
     debug!("[create_game] Game entry created with action hash: {:?}", game_action_hash);
@@ -253,23 +364,29 @@ pub fn create_game(input: CreateGameInput) -> ExternResult<Record> {
     // Link from the global "games" anchor to the game (for discoverability)
     let games_anchor_hash = anchor_for("games")?;
     create_link(games_anchor_hash, game_action_hash.clone(), LinkTypes::GameIdToGame, (),)?;
+    // Link from the daily bucket anchor (used by list_games)
+    let bucket_anchor_hash = game_bucket_anchor(day_of(game.created_at))?;
+    create_link(bucket_anchor_hash, game_action_hash.clone(), LinkTypes::GameBucketToGames, (),)?;
+    // Index the game as Waiting so lobbies can find it
+    reindex_game_status(&game_action_hash, &game.game_status, &game_action_hash)?;
     debug!("[create_game] Links created successfully.");
-
-    // Fetch and return the created record
-    let record = get(game_action_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created Game".to_string())))?;
-
-    Ok(record)
+    Ok(game_action_hash)
 }
 
 
 /// Retrieves the latest version of a game record, following the GameUpdates links.
 #[hdk_extern]
 pub fn get_latest_game(original_game_hash: ActionHash) -> ExternResult<Option<Record>> {
+    latest_game_record(original_game_hash, GetStrategy::Network)
+}
+
+/// `get_latest_game` reading with `strategy`; `GetStrategy::Local` only looks at what
+/// this node already holds and never goes to the network.
+fn latest_game_record(original_game_hash: ActionHash, strategy: GetStrategy) -> ExternResult<Option<Record>> {
     debug!("[game.rs] get_latest_game: Called with original_game_hash: {:?}", original_game_hash);
     // Get links pointing away from the original game hash with the 'GameUpdates' type
     let links_result = get_links(
-        GetLinksInputBuilder::try_new(original_game_hash.clone(), LinkTypes::GameUpdates)?.build(),
+        GetLinksInputBuilder::try_new(original_game_hash.clone(), LinkTypes::GameUpdates)?.get_options(strategy).build(),
     );
     let links = match links_result {
         Ok(l) => l,
@@ -296,7 +413,7 @@ pub fn get_latest_game(original_game_hash: ActionHash) -> ExternResult<Option<Re
 
     debug!("[game.rs] get_latest_game: Attempting to get record for hash: {:?}", latest_game_hash);
     // Get the record associated with the latest action hash
-    let result = get(latest_game_hash, GetOptions::default());
+    let result = get(latest_game_hash, GetOptions { strategy });
     debug!("[game.rs] get_latest_game: Returning record: {:?}", result.as_ref().ok().and_then(|opt_r| opt_r.as_ref().map(|r| r.action_hashed().hash.clone())));
     result
 }
@@ -357,54 +474,202 @@ pub fn get_all_revisions_for_game(original_game_hash: ActionHash) -> ExternResul
     Ok(revision_records)
 }
 
-/// Input structure for the `update_game` function.
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateGameInput {
-    pub original_game_hash: ActionHash, // Hash of the initial game creation action
-    pub previous_game_hash: ActionHash, // Hash of the action being updated
-    pub updated_game: Game,             // The new game state
+/// Fetches the latest record of a game and deserializes its entry.
+/// Returns the hash of the latest action (the one to update) alongside the game state.
+pub(crate) fn get_latest_game_state(original_game_hash: &ActionHash) -> ExternResult<(ActionHash, Game)> {
+    let latest_game_record = get_latest_game(original_game_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Game record not found for original hash {:?}", original_game_hash
+        ))))?;
+    let previous_action_hash = latest_game_record.action_hashed().hash.clone();
+    let entry = latest_game_record.entry().as_option()
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Latest game record has no entry".to_string())))?
+        .clone();
+    let game = Game::try_from(entry)?;
+    Ok((previous_action_hash, game))
 }
 
-/// Updates a game entry. Creates a new action and links it to the original game via GameUpdates.
-#[hdk_extern]
-pub fn update_game(input: UpdateGameInput) -> ExternResult<Record> {
-    debug!("[game.rs] update_game: Called with input: {:?}", input);
-
-    // Commit the update action, referencing the previous action hash
-    let updated_action_hash = match update_entry(input.previous_game_hash.clone(), &input.updated_game) {
-        Ok(hash) => {
-            debug!("[game.rs] update_game: update_entry successful, new action hash: {:?}", hash);
-            hash
+/// The players of a game as this node last saw it, going to the network only when the
+/// game is not held locally or `agent` is not one of its players there (e.g. we have
+/// not seen the join yet). Per-frame signal checks use this instead of
+/// `get_latest_game_state` so a steady stream of signals causes no network reads.
+pub(crate) fn get_game_players_local_first(original_game_hash: &ActionHash, agent: &AgentPubKey) -> ExternResult<(AgentPubKey, Option<AgentPubKey>)> {
+    let players_of = |record: Option<Record>| -> Option<(AgentPubKey, Option<AgentPubKey>)> {
+        let game = record?.entry().to_app_option::<Game>().ok().flatten()?;
+        Some((game.player_1, game.player_2))
+    };
+    if let Some((player_1, player_2)) = players_of(latest_game_record(original_game_hash.clone(), GetStrategy::Local)?) {
+        if player_1 == *agent || player_2.as_ref() == Some(agent) {
+            return Ok((player_1, player_2));
         }
-        Err(e) => {
-            debug!("[game.rs] update_game: update_entry failed: {:?}", e);
-            return Err(e);
+    }
+    players_of(latest_game_record(original_game_hash.clone(), GetStrategy::Network)?).ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+        "Game record not found for original hash {:?}", original_game_hash
+    ))))
+}
+
+/// Keeps the status index anchors in sync after a game enters `status`:
+/// removes our own links for the game from every other index and, if `status` is indexed,
+/// links `action_hash` (the Game action that entered it) from that status's anchor.
+/// Only a link's author may delete it, so links left by the other player stay behind
+/// until they clean them up; `get_games_by_status` skips those stale entries.
+fn reindex_game_status(original_game_hash: &ActionHash, status: &GameStatus, action_hash: &ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    for indexed_status in [GameStatus::Waiting, GameStatus::InProgress] {
+        if indexed_status == *status {
+            continue;
+        }
+        let Some(index_path) = status_index_path(&indexed_status) else { continue };
+        let links = get_links(GetLinksInputBuilder::try_new(anchor_for(index_path)?, LinkTypes::GameStatusIndex)?.build())?;
+        for link in links {
+            match GameStatusIndexTag::from_link_tag(&link.tag) {
+                Ok(tag) if tag.game_id == *original_game_hash && link.author == my_pub_key => {
+                    delete_link(link.create_link_hash)?;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("[game.rs] reindex_game_status: Skipping index link with malformed tag: {:?}", e),
+            }
         }
+    }
+    if let Some(index_path) = status_index_path(status) {
+        let tag = GameStatusIndexTag { status: status.clone(), game_id: original_game_hash.clone() };
+        create_link(anchor_for(index_path)?, action_hash.clone(), LinkTypes::GameStatusIndex, tag.to_link_tag()?)?;
+    }
+    Ok(())
+}
+
+/// Lists games currently in an indexed status ('Waiting' or 'InProgress') from its index anchor.
+/// Each record is the game's latest state; a 'Waiting' record is the original create of the game.
+#[hdk_extern]
+pub fn get_games_by_status(status: GameStatus) -> ExternResult<Vec<Record>> {
+    let Some(index_path) = status_index_path(&status) else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Games with status {:?} are not indexed", status))));
     };
+    let links = get_links(GetLinksInputBuilder::try_new(anchor_for(index_path)?, LinkTypes::GameStatusIndex)?.build())?;
+
+    // Index links may outlive the status they record (see `reindex_game_status`),
+    // so list each game once and only if its latest state still has this status.
+    let mut seen_game_ids = HashSet::new();
+    let mut records = Vec::new();
+    for link in links {
+        let tag = match GameStatusIndexTag::from_link_tag(&link.tag) {
+            Ok(tag) => tag,
+            Err(e) => {
+                warn!("[game.rs] get_games_by_status: Skipping index link with malformed tag: {:?}", e);
+                continue;
+            }
+        };
+        if !seen_game_ids.insert(tag.game_id.clone()) {
+            continue;
+        }
+        let Some(record) = get_latest_game(tag.game_id)? else { continue };
+        let Ok(Some(game)) = record.entry().to_app_option::<Game>() else { continue };
+        if game.game_status == status {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
 
-    // Create a link from the original game to this new update action
-    match create_link(
-        input.original_game_hash.clone(),
-        updated_action_hash.clone(),
+/// Commits a game state transition and links it from the original game via GameUpdates.
+fn commit_game_update(original_game_hash: &ActionHash, previous_action_hash: ActionHash, updated_game: &Game) -> ExternResult<Record> {
+    let update_action_hash = update_entry(previous_action_hash, updated_game)?;
+    create_link(
+        original_game_hash.clone(),
+        update_action_hash.clone(),
         LinkTypes::GameUpdates,
         (),
-    ) {
-        Ok(_) => {
-            debug!("[game.rs] update_game: create_link for GameUpdates successful.");
-        }
-        Err(e) => {
-            debug!("[game.rs] update_game: create_link for GameUpdates failed: {:?}", e);
-            // Decide if we should return early or try to get the record anyway.
-            // For now, let's assume linking is critical for consistency.
-            return Err(e);
-        }
+    )?;
+    reindex_game_status(original_game_hash, &updated_game.game_status, &update_action_hash)?;
+    get(update_action_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Could not find the updated Game record: {:?}", update_action_hash
+        ))))
+}
+
+/// Input structure for the `finish_game` function.
+/// Positions are the final snapshot of the match, stored for informational purposes.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FinishGameInput {
+    pub game_id: ActionHash, // Original game creation hash
+    pub result: CoSignedMatchResult, // Final result signed by both players
+    pub player_1_paddle: u32,
+    pub player_2_paddle: u32,
+    pub ball_x: u32,
+    pub ball_y: u32,
+}
+
+/// Transitions an 'InProgress' game to 'Finished'. Only participants may finish a game.
+/// Both players call this at game end, so finishing an already Finished game returns its latest record.
+#[hdk_extern]
+pub fn finish_game(input: FinishGameInput) -> ExternResult<Record> {
+    let caller_pubkey = agent_info()?.agent_latest_pubkey;
+    debug!("[game.rs] finish_game: Agent {:?} finishing game {:?}", caller_pubkey, input.game_id);
+
+    let (previous_action_hash, current_game) = get_latest_game_state(&input.game_id)?;
+
+    if caller_pubkey != current_game.player_1 && current_game.player_2.as_ref() != Some(&caller_pubkey) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot finish game: Caller is not a participant in this game".into())));
+    }
+    if current_game.game_status == GameStatus::Finished {
+        debug!("[game.rs] finish_game: Game {:?} is already Finished, returning latest record", input.game_id);
+        crate::tournament::advance_after_game(&current_game);
+        crate::match_series::advance_after_game(&current_game);
+        return get(previous_action_hash, GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the latest Game record".to_string())));
+    }
+    if current_game.game_status != GameStatus::InProgress {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot finish game: Game status is not 'InProgress', it's {:?}", current_game.game_status
+        ))));
+    }
+
+    if input.result.result.game_id != input.game_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot finish game: Match result is for a different game".into())));
+    }
+    crate::match_result::check_co_signed_result(&input.result)?;
+
+    let finished_game = Game {
+        game_status: GameStatus::Finished,
+        result: Some(input.result),
+        player_1_paddle: input.player_1_paddle,
+        player_2_paddle: input.player_2_paddle,
+        ball_x: input.ball_x,
+        ball_y: input.ball_y,
+        ..current_game
     };
+    let record = commit_game_update(&input.game_id, previous_action_hash, &finished_game)?;
+    // Bracket games: the winner moves on to their next match; series games: the next game is set up
+    crate::tournament::advance_after_game(&finished_game);
+    crate::match_series::advance_after_game(&finished_game);
+    Ok(record)
+}
 
-    // Fetch and return the newly created update record
-    let record = get(updated_action_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest("Could not find the newly updated Game record".to_string())
-    ))?;
-    debug!("[game.rs] update_game: Successfully processed, returning record for action: {:?}", record.action_hashed().hash);
+/// Transitions a 'Waiting' game nobody joined to 'Canceled'. Only listed participants may cancel.
+#[hdk_extern]
+pub fn cancel_game(original_game_hash: ActionHash) -> ExternResult<Record> {
+    let caller_pubkey = agent_info()?.agent_latest_pubkey;
+    debug!("[game.rs] cancel_game: Agent {:?} canceling game {:?}", caller_pubkey, original_game_hash);
+
+    let (previous_action_hash, current_game) = get_latest_game_state(&original_game_hash)?;
+
+    if caller_pubkey != current_game.player_1 && current_game.player_2.as_ref() != Some(&caller_pubkey) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot cancel game: Caller is not a participant in this game".into())));
+    }
+    if current_game.game_status != GameStatus::Waiting {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot cancel game: Game status is not 'Waiting', it's {:?}", current_game.game_status
+        ))));
+    }
+
+    let canceled_game = Game {
+        game_status: GameStatus::Canceled,
+        ..current_game
+    };
+    let record = commit_game_update(&original_game_hash, previous_action_hash, &canceled_game)?;
+    // Canceling a bracket or series game forfeits it
+    crate::tournament::advance_after_game(&canceled_game);
+    crate::match_series::advance_after_game(&canceled_game);
     Ok(record)
 }
 
@@ -449,82 +714,9 @@ pub fn delete_game(original_game_hash: ActionHash) -> ExternResult<ActionHash> {
 }
 
 
-// --- Presence and Invitation Logic ---
-
-/// Creates a Presence entry and links it from the global "presence" anchor.
-#[hdk_extern]
-pub fn publish_presence(_: ()) -> ExternResult<ActionHash> {
-    let agent = agent_info()?.agent_latest_pubkey;
-    let now = sys_time()?.as_millis();
-    // Convert timestamp safely
-    let timestamp_u64 = now.try_into().map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Timestamp conversion error (i64 to u64): {}", e))))?;
-
-    let presence = Presence { agent_pubkey: agent, timestamp: timestamp_u64 };
-    // Create the entry
-    let presence_action_hash = create_entry(&EntryTypes::Presence(presence.clone()))?;
-    // Link from anchor
-    let presence_anchor_hash = anchor_for("presence")?;
-    create_link( presence_anchor_hash, presence_action_hash.clone(), LinkTypes::Presence, (), )?;
-    Ok(presence_action_hash)
-}
-
-/// Retrieves a list of AgentPubKeys considered "online" based on recent Presence entries.
-#[hdk_extern]
-pub fn get_online_users(_: ()) -> ExternResult<Vec<AgentPubKey>> {
-     let presence_anchor_hash = anchor_for("presence")?;
-    // Get links from the presence anchor
-    let links = get_links( GetLinksInputBuilder::try_new(presence_anchor_hash, LinkTypes::Presence)?.build(), )?;
-    let mut online_agents: Vec<AgentPubKey> = Vec::new();
-    let now_ms = sys_time()?.as_millis();
-    let cutoff = now_ms.saturating_sub(30_000); // 30 second cutoff
-
-    // Prepare batch get for presence entries
-    let get_inputs: Vec<GetInput> = links .into_iter() .filter_map(|link| link.target.into_action_hash()) .map(|ah| GetInput::new(ah.into(), GetOptions::default())) .collect();
-     if get_inputs.is_empty() { return Ok(vec![]); }
-
-    // Fetch presence records
-    let records_result = HDK.with(|hdk| hdk.borrow().get(get_inputs));
-    let records = match records_result {
-      Ok(records) => records,
-      Err(e) => return Err(wasm_error!(WasmErrorInner::Guest(format!("Failed to get presence records: {:?}", e))))
-    };
-
-    // Process records to find recent ones
-    for record_option in records {
-        if let Some(record) = record_option {
-             if let Some(entry_data) = record.entry().as_option() {
-                 // Try to deserialize as Presence entry
-                 if let Ok(presence) = Presence::try_from(entry_data.clone()) {
-                     // Convert cutoff safely for comparison
-                     let cutoff_u64 = u64::try_from(cutoff).unwrap_or(0);
-                     // Check if timestamp is recent and agent not already added
-                     if presence.timestamp >= cutoff_u64 {
-                         if !online_agents.contains(&presence.agent_pubkey) {
-                             online_agents.push(presence.agent_pubkey);
-                         }
-                     }
-                 } else { warn!("Failed to deserialize Presence entry for record: {:?}", record.action_hashed().hash); }
-             } else { warn!("Presence record has no entry data: {:?}", record.action_hashed().hash); }
-        }
-    }
-    Ok(online_agents)
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct InvitationPayload {
-    pub game_id : ActionHash,
-    pub invitee : AgentPubKey,
-    pub message : String,
-}
-
-
-/// Data structure for game invitations.
-#[derive(Serialize, Deserialize, Debug, Clone, SerializedBytes)]
-pub struct Invitation {
-    pub game_id: ActionHash,
-    pub inviter: AgentPubKey,
-    pub message: String,
-}
+// --- Invitation Logic ---
+// Moved to invitations.rs (the Invitation entry is defined in the integrity zome)
+// Presence moved to presence.rs
 
 // --- Other CRUD functions ---
 
@@ -611,6 +803,7 @@ pub fn abandon_game(original_game_hash: ActionHash) -> ExternResult<Record> {
         (),
     )?;
     debug!("[game.rs] abandon_game: Created GameUpdates link from {:?} to {:?}", original_game_hash, update_action_hash);
+    reindex_game_status(&original_game_hash, &current_game.game_status, &update_action_hash)?;
 
     // Send signal to the other player
     // The original_game_hash is the game_id the signal function expects in its payload
@@ -624,6 +817,10 @@ pub fn abandon_game(original_game_hash: ActionHash) -> ExternResult<Record> {
         }
     }
 
+    // Abandoning a bracket or series game forfeits it
+    crate::tournament::advance_after_game(&current_game);
+    crate::match_series::advance_after_game(&current_game);
+
     // 6. Fetch and return the latest record (representing the update action)
     let final_record = get(update_action_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
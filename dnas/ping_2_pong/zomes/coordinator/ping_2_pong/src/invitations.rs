@@ -1,6 +1,20 @@
 // ─── dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/invitations.rs ───
+// Game invitations (P1 ➜ P2).
+//
+// An invitation is an Invitation entry linked from the invitee's key, so it waits for
+// them if they are offline; a Signal::GameInvitation is also sent for live delivery.
+// The invitee accepts or declines it and the inviter can cancel it, each a single
+// status update checked in validation. Invitations expire at `expires_at`: after
+// that they can no longer be answered and drop out of `get_pending_invitations`.
 use hdk::prelude::*;
-use crate::{Signal, game::join_game,};
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::GameStatus;
+use ping_2_pong_integrity::invitation::MAX_INVITATION_TTL_MS;
+use crate::{Signal, game::{join_game, get_latest_game_state}};
+use crate::signals::{send_signal_to, DeliveryFailure};
+
+/// Lifetime of an invitation when the inviter does not choose one.
+const DEFAULT_INVITATION_TTL_MS: i64 = 10 * 60 * 1000;
 
 /// Data the UI passes in when one player invites another.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -8,42 +22,212 @@ pub struct InvitationPayload {
     pub game_id: ActionHash,
     pub invitee: AgentPubKey,
     pub message: String,
+    pub expires_in_ms: Option<i64>, // Defaults to 10 minutes, at most 24 hours
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AcceptInvitationPayload {
-    pub game_id: ActionHash,
+    pub invitation_hash: ActionHash, // Original Invitation create action
+}
+
+/// Result of `send_invitation`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InvitationSent {
+    pub invitation_hash: ActionHash,
+    pub delivery_failure: Option<DeliveryFailure>, // Set when the invitee was not reached live
 }
 
-/// Player-to-player invitation (P1 ➜ P2)
+/// An open invitation addressed to us.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingInvitation {
+    pub invitation_hash: ActionHash,
+    pub invitation: Invitation,
+}
+
+/// Invites another player to our Waiting game. The invitation is stored for them
+/// either way; `delivery_failure` only says whether they saw it immediately.
 #[hdk_extern]
-pub fn send_invitation(payload: InvitationPayload) -> ExternResult<()> {
-    // build the signal once
-    let signal = Signal::GameInvitation {
+pub fn send_invitation(payload: InvitationPayload) -> ExternResult<InvitationSent> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if payload.invitee == my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot invite yourself".into())));
+    }
+    let (_, game) = get_latest_game_state(&payload.game_id)?;
+    if game.player_1 != my_pub_key || game.game_status != GameStatus::Waiting {
+        return Err(wasm_error!(WasmErrorInner::Guest("Can only invite to your own game while it is waiting".into())));
+    }
+
+    // 1) persist the invitation in the invitee's inbox
+    let ttl_ms = payload.expires_in_ms.unwrap_or(DEFAULT_INVITATION_TTL_MS).clamp(1, MAX_INVITATION_TTL_MS);
+    let created_at = sys_time()?;
+    let invitation = Invitation {
         game_id: payload.game_id.clone(),
-        inviter: agent_info()?.agent_latest_pubkey,
+        inviter: my_pub_key,
+        invitee: payload.invitee.clone(),
         message: payload.message.clone(),
+        created_at,
+        expires_at: Timestamp::from_micros(created_at.as_micros() + ttl_ms * 1000),
+        status: InvitationStatus::Pending,
     };
+    let invitation_hash = create_entry(&EntryTypes::Invitation(invitation.clone()))?;
+    create_link(payload.invitee.clone(), invitation_hash.clone(), LinkTypes::InviteeToInvitations, ())?;
 
-    // 1) show it in *my* UI
+    // 2) build the signal once, show it in *my* UI and deliver it to the invitee
+    let signal = Signal::GameInvitation {
+        invitation_hash: invitation_hash.clone(),
+        game_id: payload.game_id,
+        inviter: invitation.inviter,
+        message: payload.message,
+        expires_at: invitation.expires_at,
+    };
     emit_signal(&signal)?;
+    let delivery_failure = send_signal_to(vec![payload.invitee], &signal)?.pop();
 
-    // 2) fire-and-forget to the invitee
-    let _ = call_remote(
-        payload.invitee,             // who to call
-        "ping_2_pong",               // zome
-        "receive_remote_signal".into(),
-        None,                        // unrestricted cap
-        signal,                      // payload (cloned implicitly)
-    );
+    Ok(InvitationSent { invitation_hash, delivery_failure })
+}
 
-    Ok(())
+/// Open invitations addressed to us, oldest first. Links to invitations that were
+/// answered, cancelled or have expired are removed from our inbox on the way.
+#[hdk_extern]
+pub fn get_pending_invitations(_: ()) -> ExternResult<Vec<PendingInvitation>> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let now = sys_time()?;
+    let links = get_links(GetLinksInputBuilder::try_new(my_pub_key, LinkTypes::InviteeToInvitations)?.build())?;
+
+    let mut pending = Vec::new();
+    for link in links {
+        let Some(invitation_hash) = link.target.clone().into_action_hash() else { continue };
+        match get_latest_invitation(&invitation_hash) {
+            Ok(invitation) if invitation.is_open_at(now) => pending.push(PendingInvitation { invitation_hash, invitation }),
+            Ok(_) => { delete_link(link.create_link_hash)?; }
+            Err(e) => debug!("[invitations] Skipping unreadable invitation {:?}: {:?}", invitation_hash, e),
+        }
+    }
+    pending.sort_by_key(|p| p.invitation.created_at);
+    Ok(pending)
 }
 
-/// Player-2 clicks **Accept** in the UI
+/// Player-2 clicks **Accept** in the UI: joins the game, then marks the invitation accepted.
 #[hdk_extern]
 pub fn accept_invitation(payload: AcceptInvitationPayload) -> ExternResult<()> {
-    // delegate, then discard the Record so our signature stays `()`
-    join_game(payload.game_id).map(|_updated_record| ())
-    //                └──────── throw the value away, keep only the `Ok`
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let invitation = get_latest_invitation(&payload.invitation_hash)?;
+    if invitation.invitee != my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("This invitation is not addressed to you".into())));
+    }
+    ensure_open(&invitation)?;
+
+    // join first, so a failed join leaves the invitation open
+    join_game(invitation.game_id.clone())?;
+    set_status(&payload.invitation_hash, invitation, InvitationStatus::Accepted)?;
+    delete_invitation_links(my_pub_key, &payload.invitation_hash)
+}
+
+/// Player-2 clicks **Decline**: the inviter gets a Signal::InvitationDeclined.
+/// Declining an invitation that already expired just clears it from our inbox.
+#[hdk_extern]
+pub fn decline_invitation(invitation_hash: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let invitation = get_latest_invitation(&invitation_hash)?;
+    if invitation.invitee != my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("This invitation is not addressed to you".into())));
+    }
+    if invitation.is_open_at(sys_time()?) {
+        let inviter = invitation.inviter.clone();
+        set_status(&invitation_hash, invitation, InvitationStatus::Declined)?;
+        let signal = Signal::InvitationDeclined { invitation_hash: invitation_hash.clone(), invitee: my_pub_key.clone() };
+        for failure in send_signal_to(vec![inviter], &signal)? {
+            debug!("[invitations] Decline not delivered to {:?}: {}", failure.agent, failure.reason);
+        }
+    }
+    delete_invitation_links(my_pub_key, &invitation_hash)
+}
+
+/// The inviter withdraws an open invitation: it leaves the invitee's inbox and they
+/// get a Signal::InvitationCancelled. The game itself is left for `cancel_game`.
+#[hdk_extern]
+pub fn cancel_invitation(invitation_hash: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let invitation = get_latest_invitation(&invitation_hash)?;
+    if invitation.inviter != my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the inviter can cancel an invitation".into())));
+    }
+    let invitee = invitation.invitee.clone();
+    if invitation.is_open_at(sys_time()?) {
+        set_status(&invitation_hash, invitation, InvitationStatus::Cancelled)?;
+    }
+    delete_invitation_links(invitee.clone(), &invitation_hash)?;
+
+    let signal = Signal::InvitationCancelled { invitation_hash, inviter: my_pub_key };
+    for failure in send_signal_to(vec![invitee], &signal)? {
+        debug!("[invitations] Cancellation not delivered to {:?}: {}", failure.agent, failure.reason);
+    }
+    Ok(())
+}
+
+// --- helpers ---
+
+/// Current state of an invitation: the original entry, or the status update that stands.
+/// Validation checks each update against the Pending original, so an accept can race a
+/// decline or cancel. The accept stands if the invitee did join the game; otherwise a
+/// decline or cancel does. Ties go to the lowest action hash, so every agent resolves
+/// the same updates the same way.
+fn get_latest_invitation(invitation_hash: &ActionHash) -> ExternResult<Invitation> {
+    let Some(Details::Record(details)) = get_details(invitation_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Invitation not found: {:?}", invitation_hash))));
+    };
+    let original = invitation_from_record(&details.record)?;
+    let mut updates = Vec::with_capacity(details.updates.len());
+    for update in &details.updates {
+        let record = get(update.as_hash().clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Invitation update not found".into())))?;
+        updates.push((update.as_hash().clone(), invitation_from_record(&record)?));
+    }
+
+    let invitee_joined = if updates.iter().any(|(_, update)| update.status == InvitationStatus::Accepted) {
+        let (_, game) = get_latest_game_state(&original.game_id)?;
+        game.player_2.as_ref() == Some(&original.invitee)
+    } else {
+        false
+    };
+    let rank = |status: &InvitationStatus| match status {
+        InvitationStatus::Accepted if invitee_joined => 0,
+        InvitationStatus::Declined | InvitationStatus::Cancelled => 1,
+        _ => 2,
+    };
+    Ok(updates
+        .into_iter()
+        .min_by_key(|(hash, update)| (rank(&update.status), hash.clone()))
+        .map_or(original, |(_, update)| update))
+}
+
+fn invitation_from_record(record: &Record) -> ExternResult<Invitation> {
+    let entry = record.entry().as_option()
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invitation record has no entry".into())))?
+        .clone();
+    Invitation::try_from(entry)
+}
+
+fn ensure_open(invitation: &Invitation) -> ExternResult<()> {
+    if invitation.status != InvitationStatus::Pending {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Invitation was already {:?}", invitation.status))));
+    }
+    if !invitation.is_open_at(sys_time()?) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invitation has expired".into())));
+    }
+    Ok(())
+}
+
+fn set_status(invitation_hash: &ActionHash, invitation: Invitation, status: InvitationStatus) -> ExternResult<ActionHash> {
+    update_entry(invitation_hash.clone(), &EntryTypes::Invitation(Invitation { status, ..invitation }))
+}
+
+fn delete_invitation_links(invitee: AgentPubKey, invitation_hash: &ActionHash) -> ExternResult<()> {
+    let links = get_links(GetLinksInputBuilder::try_new(invitee, LinkTypes::InviteeToInvitations)?.build())?;
+    for link in links {
+        if link.target.clone().into_action_hash().as_ref() == Some(invitation_hash) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
 }
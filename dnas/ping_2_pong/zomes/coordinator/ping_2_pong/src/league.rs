@@ -0,0 +1,337 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/league.rs
+// Seasons and round-robin leagues.
+//
+// - A Season is a window of time, linked from the "seasons" anchor. Leagues run in a
+//   season, and the rating leaderboard can be scoped to one (see rating.rs).
+// - A League fixes its members at creation; its schedule is `round_robin_schedule`
+//   of the members, so it is never stored.
+// - A fixture is played as an ordinary invite-only Game, created by the fixture's
+//   player 1 and linked from the league with a LeagueFixtureTag. Player 2 starts it
+//   by joining, as for an invitation.
+// - Standings are computed from the co-signed results of the finished fixture games,
+//   read from their Score entries (GameToScores).
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::{GameStatus, GameVisibility};
+use ping_2_pong_integrity::league::{
+    LeagueFixtureTag, round_robin_schedule,
+    MIN_LEAGUE_MEMBERS, MAX_LEAGUE_MEMBERS, MAX_LEAGUE_NAME_LENGTH,
+};
+use ping_2_pong_integrity::match_result::MatchResult;
+use crate::Signal;
+use crate::game::{create_game, get_latest_game, CreateGameInput};
+use crate::utils::{anchor_for, player_exists};
+use crate::signals::send_signal_to;
+
+/// Input for `create_season`; the caller is the creator.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateSeasonInput {
+    pub name: String,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp, // Exclusive
+}
+
+/// Input for `create_league`; the caller is the organiser.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateLeagueInput {
+    pub name: String,
+    pub season_id: ActionHash, // Season create action
+    pub members: Vec<AgentPubKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlayFixtureInput {
+    pub league_id: ActionHash, // League create action
+    pub round: u32,
+    pub index: u32,
+}
+
+/// One fixture of a league's schedule and, once played, its game and result.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeagueFixture {
+    pub round: u32,
+    pub index: u32,
+    pub player_1: AgentPubKey,
+    pub player_2: AgentPubKey,
+    pub game_id: Option<ActionHash>, // Original Game create action, once the fixture is created
+    pub status: Option<GameStatus>,
+    pub result: Option<MatchResult>, // Co-signed result once the game is finished
+}
+
+/// A member's record in a league.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeagueStanding {
+    pub player: AgentPubKey,
+    pub played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub points_for: u32,
+    pub points_against: u32,
+    pub point_differential: i64,
+}
+
+/// Creates a season, linked from the "seasons" anchor.
+#[hdk_extern]
+pub fn create_season(input: CreateSeasonInput) -> ExternResult<Record> {
+    let name = check_name(&input.name)?;
+    if input.ends_at <= input.starts_at {
+        return Err(wasm_error!(WasmErrorInner::Guest("Season must end after it starts".into())));
+    }
+    let season = Season {
+        name,
+        created_by: agent_info()?.agent_latest_pubkey,
+        starts_at: input.starts_at,
+        ends_at: input.ends_at,
+        created_at: sys_time()?,
+    };
+    let season_hash = create_entry(&EntryTypes::Season(season))?;
+    create_link(anchor_for("seasons")?, season_hash.clone(), LinkTypes::AllSeasons, ())?;
+    get(season_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created Season".to_string())))
+}
+
+/// Every season, latest start first.
+#[hdk_extern]
+pub fn get_seasons(_: ()) -> ExternResult<Vec<(ActionHash, Season)>> {
+    let links = get_links(GetLinksInputBuilder::try_new(anchor_for("seasons")?, LinkTypes::AllSeasons)?.build())?;
+    let mut seasons = Vec::new();
+    for season_id in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        match get_season(&season_id) {
+            Ok(season) => seasons.push((season_id, season)),
+            Err(e) => debug!("[league] Skipping unreadable season {:?}: {:?}", season_id, e),
+        }
+    }
+    seasons.sort_by_key(|(_, season)| std::cmp::Reverse(season.starts_at));
+    Ok(seasons)
+}
+
+/// Creates a league in a season that has not ended, organised by the caller.
+#[hdk_extern]
+pub fn create_league(input: CreateLeagueInput) -> ExternResult<Record> {
+    let name = check_name(&input.name)?;
+    let mut members: Vec<AgentPubKey> = Vec::new();
+    for member in input.members {
+        if !members.contains(&member) {
+            members.push(member);
+        }
+    }
+    if members.len() < MIN_LEAGUE_MEMBERS || members.len() > MAX_LEAGUE_MEMBERS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "League must have between {} and {} members", MIN_LEAGUE_MEMBERS, MAX_LEAGUE_MEMBERS
+        ))));
+    }
+    for member in &members {
+        if !player_exists(member)? {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!("League member {} is not a registered player", member))));
+        }
+    }
+    let season = get_season(&input.season_id)?;
+    let created_at = sys_time()?;
+    if created_at >= season.ends_at {
+        return Err(wasm_error!(WasmErrorInner::Guest("This season has already ended".into())));
+    }
+    let league = League {
+        name,
+        organiser: agent_info()?.agent_latest_pubkey,
+        season_id: input.season_id.clone(),
+        members,
+        created_at,
+    };
+    let league_hash = create_entry(&EntryTypes::League(league))?;
+    create_link(input.season_id, league_hash.clone(), LinkTypes::SeasonToLeagues, ())?;
+    get(league_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created League".to_string())))
+}
+
+/// Leagues of a season, oldest first.
+#[hdk_extern]
+pub fn get_leagues_for_season(season_id: ActionHash) -> ExternResult<Vec<(ActionHash, League)>> {
+    let links = get_links(GetLinksInputBuilder::try_new(season_id, LinkTypes::SeasonToLeagues)?.build())?;
+    let mut leagues = Vec::new();
+    for league_id in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        match get_league(&league_id) {
+            Ok(league) => leagues.push((league_id, league)),
+            Err(e) => debug!("[league] Skipping unreadable league {:?}: {:?}", league_id, e),
+        }
+    }
+    leagues.sort_by_key(|(_, league)| league.created_at);
+    Ok(leagues)
+}
+
+/// The league's schedule, round by round, with the game and result of every fixture played so far.
+#[hdk_extern]
+pub fn get_league_schedule(league_id: ActionHash) -> ExternResult<Vec<Vec<LeagueFixture>>> {
+    let league = get_league(&league_id)?;
+    let games = fixture_games(&league_id)?;
+    let mut rounds = Vec::new();
+    for (round, fixtures) in round_robin_schedule(&league.members).into_iter().enumerate() {
+        let mut scheduled = Vec::with_capacity(fixtures.len());
+        for (index, (player_1, player_2)) in fixtures.into_iter().enumerate() {
+            let tag = LeagueFixtureTag { round: round as u32, index: index as u32 };
+            let mut fixture = LeagueFixture {
+                round: tag.round, index: tag.index, player_1, player_2, game_id: None, status: None, result: None,
+            };
+            // A fixture abandoned or canceled before it finished can be created again, so
+            // until one of its games finishes the latest linked game is the one shown.
+            // The first game to finish is final: games linked after it are ignored.
+            for (_, game_id) in games.iter().filter(|(game_tag, _)| *game_tag == tag) {
+                let Some(game) = get_latest_game(game_id.clone())?.and_then(|record| record.entry().to_app_option::<Game>().ok().flatten()) else {
+                    continue;
+                };
+                fixture.game_id = Some(game_id.clone());
+                fixture.status = Some(game.game_status.clone());
+                if game.game_status == GameStatus::Finished {
+                    fixture.result = fixture_result(game_id, &game)?;
+                    break;
+                }
+            }
+            scheduled.push(fixture);
+        }
+        rounds.push(scheduled);
+    }
+    Ok(rounds)
+}
+
+/// Creates the game for one of the caller's fixtures, as its player 1, and tells player 2.
+/// Returns the existing game if the fixture is already waiting, in progress or finished.
+#[hdk_extern]
+pub fn play_league_fixture(input: PlayFixtureInput) -> ExternResult<ActionHash> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let league = get_league(&input.league_id)?;
+    let season = get_season(&league.season_id)?;
+    if !season.contains(sys_time()?) {
+        return Err(wasm_error!(WasmErrorInner::Guest("League fixtures can only be played during their season".into())));
+    }
+    let schedule = get_league_schedule(input.league_id.clone())?;
+    let fixture = schedule
+        .get(input.round as usize)
+        .and_then(|round| round.get(input.index as usize))
+        .ok_or(wasm_error!(WasmErrorInner::Guest("No such fixture in this league".into())))?;
+    if fixture.player_1 != my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the fixture's player 1 can start it".into())));
+    }
+    if let (Some(game_id), Some(status)) = (&fixture.game_id, &fixture.status) {
+        if !matches!(status, GameStatus::Abandoned | GameStatus::Canceled) {
+            return Ok(game_id.clone());
+        }
+    }
+
+    let record = create_game(CreateGameInput {
+        player_1: fixture.player_1.clone(),
+        player_2: Some(fixture.player_2.clone()),
+        visibility: GameVisibility::InviteOnly, // The seat is reserved for player 2
+    })?;
+    let game_id = record.action_address().clone();
+    let tag = LeagueFixtureTag { round: input.round, index: input.index };
+    create_link(input.league_id.clone(), game_id.clone(), LinkTypes::LeagueToGames, tag.to_link_tag()?)?;
+
+    let signal = Signal::LeagueFixtureReady {
+        league_id: input.league_id,
+        game_id: game_id.clone(),
+        player_1: fixture.player_1.clone(),
+        player_2: fixture.player_2.clone(),
+    };
+    for failure in send_signal_to(vec![fixture.player_2.clone()], &signal)? {
+        debug!("[league] Fixture ready not delivered to {:?}: {}", failure.agent, failure.reason);
+    }
+    Ok(game_id)
+}
+
+/// League table: wins first, then point differential, then points scored.
+/// Every member is listed, including those who have not played yet.
+#[hdk_extern]
+pub fn get_league_standings(league_id: ActionHash) -> ExternResult<Vec<LeagueStanding>> {
+    let league = get_league(&league_id)?;
+    let mut standings: Vec<LeagueStanding> = league
+        .members
+        .iter()
+        .map(|player| LeagueStanding {
+            player: player.clone(),
+            played: 0, wins: 0, losses: 0, draws: 0, points_for: 0, points_against: 0, point_differential: 0,
+        })
+        .collect();
+
+    for fixture in get_league_schedule(league_id)?.into_iter().flatten() {
+        let Some(result) = fixture.result else { continue };
+        for (player, points_for, points_against) in [
+            (&result.player_1, result.score1, result.score2),
+            (&result.player_2, result.score2, result.score1),
+        ] {
+            let Some(standing) = standings.iter_mut().find(|standing| standing.player == *player) else { continue };
+            standing.played += 1;
+            match &result.winner {
+                Some(winner) if winner == player => standing.wins += 1,
+                Some(_) => standing.losses += 1,
+                None => standing.draws += 1,
+            }
+            standing.points_for += points_for;
+            standing.points_against += points_against;
+            standing.point_differential += points_for as i64 - points_against as i64;
+        }
+    }
+
+    standings.sort_by(|a, b| {
+        b.wins.cmp(&a.wins)
+            .then_with(|| b.point_differential.cmp(&a.point_differential))
+            .then_with(|| b.points_for.cmp(&a.points_for))
+            .then_with(|| a.player.cmp(&b.player)) // Consistent tie-breaking
+    });
+    Ok(standings)
+}
+
+// --- helpers ---
+
+fn check_name(name: &str) -> ExternResult<String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_LEAGUE_NAME_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Name must be 1 to {} characters", MAX_LEAGUE_NAME_LENGTH))));
+    }
+    Ok(name)
+}
+
+/// Seasons cannot be updated, so the create action holds the season.
+pub(crate) fn get_season(season_id: &ActionHash) -> ExternResult<Season> {
+    get(season_id.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Season not found: {:?}", season_id))))?
+        .entry()
+        .to_app_option::<Season>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record is not a Season entry".into())))
+}
+
+/// Leagues cannot be updated either.
+pub(crate) fn get_league(league_id: &ActionHash) -> ExternResult<League> {
+    get(league_id.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("League not found: {:?}", league_id))))?
+        .entry()
+        .to_app_option::<League>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record is not a League entry".into())))
+}
+
+/// Games linked to the league with their fixture, earliest link first.
+fn fixture_games(league_id: &ActionHash) -> ExternResult<Vec<(LeagueFixtureTag, ActionHash)>> {
+    let mut links = get_links(GetLinksInputBuilder::try_new(league_id.clone(), LinkTypes::LeagueToGames)?.build())?;
+    links.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.create_link_hash.cmp(&b.create_link_hash)));
+    Ok(links
+        .into_iter()
+        .filter_map(|link| {
+            let tag = LeagueFixtureTag::from_link_tag(&link.tag).ok()?;
+            Some((tag, link.target.into_action_hash()?))
+        })
+        .collect())
+}
+
+/// Result of a finished fixture game, as recorded in its Score entries; falls back to
+/// the result on the game itself while the scores are still being written.
+fn fixture_result(game_id: &ActionHash, game: &Game) -> ExternResult<Option<MatchResult>> {
+    for record in crate::score::get_scores_for_game(game_id.clone())? {
+        if let Ok(Some(score)) = record.entry().to_app_option::<Score>() {
+            if score.game_id == *game_id {
+                return Ok(Some(score.result.result));
+            }
+        }
+    }
+    Ok(game.result.as_ref().map(|co_signed| co_signed.result.clone()))
+}
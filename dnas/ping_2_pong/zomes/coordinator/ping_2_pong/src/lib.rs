@@ -9,6 +9,17 @@ pub mod statistics;
 pub mod utils;
 pub mod signals;
 pub mod invitations;
+pub mod match_result;
+pub mod physics;
+pub mod replay;
+pub mod rating;
+pub mod matchmaking;
+pub mod presence;
+pub mod direct_messages;
+pub mod spectate;
+pub mod tournament;
+pub mod league;
+pub mod match_series;
 
 pub use chat::send_global_chat_message;
 pub use signals::receive_remote_signal;
@@ -20,6 +31,7 @@ use ping_2_pong_integrity::*;
 // NEW STRUCT DEFINITION
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ChatMessagePayload {
+    pub action_hash: ActionHash, // ChatMessage create action, for de-duplication against history
     pub timestamp: Timestamp,
     pub sender: AgentPubKey,
     pub content: String,
@@ -28,18 +40,8 @@ pub struct ChatMessagePayload {
 /// ---------- 1. grant the capability on startup ----------
 #[hdk_extern]
 fn init(_: ()) -> ExternResult<InitCallbackResult> {
-    // everybody can call `receive_remote_signal`
-    let grant = CapGrantEntry {
-        tag: "remote-signal".into(),
-        access: CapAccess::Unrestricted,
-        functions: GrantedFunctions::Listed(
-            vec![("ping_2_pong".into(), "receive_remote_signal".into())]
-                .into_iter()
-                .map(Into::into)
-                .collect(),
-        ),
-    };
-    create_cap_grant(grant)?;
+    // everybody can call `receive_remote_signal` & co. (see signals.rs)
+    signals::grant_remote_signal_cap()?;
     Ok(InitCallbackResult::Pass)
 }
 
@@ -49,6 +51,28 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
 #[serde(tag = "type")]
 pub enum Signal {
     GlobalChatMessage(ChatMessagePayload),
+    // The sender deleted one of their chat messages
+    GlobalChatMessageDeleted { action_hash: ActionHash },
+    // Private message from another player (see direct_messages.rs)
+    DirectMessage {
+        sender: AgentPubKey,
+        content: String,
+        timestamp: Timestamp,
+    },
+    // The recipient read a message we left in their inbox
+    DirectMessageDelivered {
+        inbox_hash: ActionHash,
+        recipient: AgentPubKey,
+        received_at: Timestamp,
+    },
+    // Chat between the players of one game (see chat.rs)
+    GameChatMessage {
+        game_id: ActionHash,
+        sender: AgentPubKey,
+        content: String,
+        timestamp: Timestamp,
+        action_hash: Option<ActionHash>, // Set when the message was persisted
+    },
     // Standard Holochain signals
     LinkCreated { action: SignedActionHashed, link_type: LinkTypes },
     LinkDeleted { action: SignedActionHashed, create_link_action: SignedActionHashed, link_type: LinkTypes },
@@ -58,9 +82,21 @@ pub enum Signal {
 
     // Custom application signals
     GameInvitation {
+        invitation_hash: ActionHash, // Invitation entry, for accept/decline (see invitations.rs)
         game_id: ActionHash,
         inviter: AgentPubKey,
         message: String,
+        expires_at: Timestamp,
+    },
+    // The invitee turned down one of our invitations
+    InvitationDeclined {
+        invitation_hash: ActionHash,
+        invitee: AgentPubKey,
+    },
+    // The inviter withdrew an invitation they sent us
+    InvitationCancelled {
+        invitation_hash: ActionHash,
+        inviter: AgentPubKey,
     },
     // *** MODIFIED GameStarted to include both players ***
     GameStarted {
@@ -73,8 +109,25 @@ pub enum Signal {
         player: AgentPubKey,
         paddle_y: u32,
     },
+    // Tick-stamped paddle input; receivers keep only the newest tick from the opponent
+    PaddleInput {
+        game_id: ActionHash,
+        player: AgentPubKey,
+        tick: u64,
+        paddle_y: u32,
+    },
+    // Acknowledges every input from `player`'s opponent up to and including `ack_tick`.
+    // `applied_tick` is the tick in `player`'s own timeline from which the `ack_tick` input took effect.
+    InputAck {
+        game_id: ActionHash,
+        player: AgentPubKey,
+        ack_tick: u64,
+        applied_tick: u64,
+    },
+    // Player 1's simulated ball after `tick` (see physics.rs), in whole pixels
     BallUpdate {
         game_id: ActionHash,
+        tick: u64,
         ball_x: u32,
         ball_y: u32,
         ball_dx: i32,
@@ -95,6 +148,64 @@ pub enum Signal {
         game_id: ActionHash,
         abandoned_by_player: AgentPubKey,
     },
+    // One player's signature over the final result, sent to the opponent
+    MatchResultSigned {
+        game_id: ActionHash,
+        result: MatchResult,
+        signer: AgentPubKey,
+        signature: Signature,
+    },
+    // Matchmaking paired two queued players; player 2 joins the created game
+    MatchFound {
+        game_id: ActionHash,
+        player_1: AgentPubKey,
+        player_2: AgentPubKey,
+    },
+    // Gossip presence (PresenceMode::Gossip): a peer's heartbeat, sent instead of a DHT write
+    PresenceHeartbeat {
+        agent: AgentPubKey,
+        status: OnlineStatus,
+        timestamp: u64, // Milliseconds since epoch
+    },
+    // Gossip presence: a peer closed the app
+    PresenceLeft {
+        agent: AgentPubKey,
+    },
+    // A bracket game was created for the two players (see tournament.rs); player 2 starts it by joining
+    TournamentMatchReady {
+        tournament_id: ActionHash,
+        game_id: ActionHash,
+        round: u32,
+        player_1: AgentPubKey,
+        player_2: AgentPubKey,
+    },
+    // Player 1 created the game for a league fixture (see league.rs); player 2 starts it by joining
+    LeagueFixtureReady {
+        league_id: ActionHash,
+        game_id: ActionHash,
+        player_1: AgentPubKey,
+        player_2: AgentPubKey,
+    },
+    // A series game ended without deciding the series (see match_series.rs). `next_game_id` is
+    // set once the next game's player 1 has created it; until then they are asked to create it
+    SeriesUpdate {
+        series_id: ActionHash,
+        player_1: AgentPubKey, // The series' players, as in the MatchSeries entry
+        player_2: AgentPubKey,
+        player_1_wins: u32,
+        player_2_wins: u32,
+        game_number: u32,      // Number of the next game (0-based)
+        next_game_id: Option<ActionHash>,
+    },
+    // A player reached the wins needed and the series is recorded as Finished
+    SeriesOver {
+        series_id: ActionHash,
+        player_1: AgentPubKey,
+        player_2: AgentPubKey,
+        winner: AgentPubKey,
+        player_1_wins: u32,
+        player_2_wins: u32,
+    },
 }
 
 // post_commit hook (no changes needed here)
@@ -0,0 +1,93 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/match_result.rs
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::GameStatus;
+use ping_2_pong_integrity::match_result_validation::validate_co_signed_result;
+use crate::Signal;
+
+// Result-agreement protocol:
+// 0. When a game starts, each player calls `ensure_rating` so both have a Rating revision.
+// 1. When a game ends, Player 1's UI proposes the result (including both players' current
+//    Rating revisions) and calls `sign_match_result`; Player 2's UI checks the proposal
+//    against what it observed and signs the same result.
+// 2. The zome signs it and relays the signature to the opponent via `call_remote`
+//    (`Signal::MatchResultSigned`).
+// 3. Once a UI holds both signatures over the same result, it passes the
+//    `CoSignedMatchResult` to `finish_game`, `create_score` and `send_game_over`.
+// The integrity zome rejects the final Game update and every Score without both signatures.
+
+/// Signs the given result as the calling player and relays the signature to the opponent.
+#[hdk_extern]
+pub fn sign_match_result(result: MatchResult) -> ExternResult<Signature> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+
+    // The result must describe the game as it exists on the DHT
+    let game_record = crate::game::get_latest_game(result.game_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Game not found".into())))?;
+    let game = game_record
+        .entry()
+        .to_app_option::<Game>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Invalid Game entry".into())))?;
+
+    if game.game_status != GameStatus::InProgress && game.game_status != GameStatus::Finished {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot sign result: Game status is {:?}", game.game_status
+        ))));
+    }
+    if result.player_1 != game.player_1 || Some(&result.player_2) != game.player_2.as_ref() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Players do not match the game".into())));
+    }
+    if my_pub_key != result.player_1 && my_pub_key != result.player_2 {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Caller is not a participant in this game".into())));
+    }
+    // The result must name the join that seated Player 2, so it cannot be reused for a forged seat
+    let join_record = get(result.join_action.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Join action not found".into())))?;
+    let joined_game = join_record
+        .entry()
+        .to_app_option::<Game>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+    let is_join = matches!(join_record.action(), Action::Update(update) if update.original_action_address == result.game_id);
+    if !is_join || joined_game.map(|g| g.game_status) != Some(GameStatus::InProgress) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot sign result: join_action is not the join of this game".into())));
+    }
+
+    // Each player vouches for their own pre-game rating: it must be their latest revision
+    let (my_rating, opponent, opponent_rating) = if my_pub_key == result.player_1 {
+        (&result.player_1_rating, &result.player_2, &result.player_2_rating)
+    } else {
+        (&result.player_2_rating, &result.player_1, &result.player_1_rating)
+    };
+    if crate::rating::get_latest_rating_hash(&my_pub_key)?.as_ref() != Some(my_rating) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Your rating in the result is not your latest Rating".into())));
+    }
+    let opponent_rating_record = get(opponent_rating.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Opponent's Rating not found".into())))?;
+    match opponent_rating_record.entry().to_app_option::<Rating>() {
+        Ok(Some(rating)) if rating.player == *opponent => {}
+        _ => return Err(wasm_error!(WasmErrorInner::Guest("Cannot sign result: Opponent's rating in the result is not their Rating".into()))),
+    }
+
+    let signature = sign(my_pub_key.clone(), &result)?;
+
+    let signal = Signal::MatchResultSigned {
+        game_id: result.game_id.clone(),
+        result: result.clone(),
+        signer: my_pub_key,
+        signature: signature.clone(),
+    };
+    crate::signals::broadcast_to_opponents(&result.game_id, &signal)?;
+
+    Ok(signature)
+}
+
+/// Checks a co-signed result with the same rules the integrity zome applies,
+/// so callers get a clear error before committing anything.
+pub fn check_co_signed_result(co_signed: &CoSignedMatchResult) -> ExternResult<()> {
+    match validate_co_signed_result(co_signed)? {
+        ValidateCallbackResult::Valid => Ok(()),
+        ValidateCallbackResult::Invalid(reason) => Err(wasm_error!(WasmErrorInner::Guest(reason))),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!("Could not verify match result: {:?}", other)))),
+    }
+}
@@ -0,0 +1,298 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/match_series.rs
+// Best-of-N match series.
+//
+// - The challenger creates a MatchSeries entry (best of 3, 5 or 7), linked from both
+//   players with PlayerToSeries, and the first game.
+// - Series games are ordinary invite-only Games carrying the series id, linked from
+//   the series with a SeriesGameTag. Sides swap every game, so the challenger is
+//   player 1 of the even-numbered games and the opponent of the odd ones; each game
+//   is created by its player 1 and started by player 2 joining it.
+// - When a series game ends (`advance_after_game`), the series either has a winner,
+//   who is recorded with the games they won and announced with SeriesOver, or the
+//   next game is created and announced with SeriesUpdate. Whoever walks out of a game
+//   forfeits it, and a drawn game is simply replayed.
+//
+// The score is never stored: `get_series` derives it from the linked games.
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::{GameStatus, GameVisibility};
+use ping_2_pong_integrity::match_series::{SeriesGameTag, SeriesWin, SERIES_LENGTHS};
+use crate::Signal;
+use crate::game::{commit_new_game, get_latest_game};
+use crate::utils::player_exists;
+use crate::signals::send_signal_to;
+
+/// Input for `create_series`; the caller challenges `opponent`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateSeriesInput {
+    pub opponent: AgentPubKey,
+    pub best_of: u32, // 3, 5 or 7
+}
+
+/// One game of a series, with its result once decided.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SeriesGame {
+    pub game_number: u32,
+    pub game_id: ActionHash, // Original Game create action
+    pub player_1: AgentPubKey,
+    pub player_2: AgentPubKey,
+    pub status: GameStatus,
+    pub winner: Option<AgentPubKey>,
+    pub decided_by: Option<ActionHash>, // Game action that decided it (None while undecided or drawn)
+}
+
+/// A series with its games, oldest first, and the score derived from them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SeriesState {
+    pub series_id: ActionHash,
+    pub series: MatchSeries,
+    pub games: Vec<SeriesGame>,
+    pub player_1_wins: u32, // Wins of the series' player_1 (the challenger)
+    pub player_2_wins: u32,
+}
+
+/// Challenges `opponent` to a series and creates its first game, with the caller as player 1.
+#[hdk_extern]
+pub fn create_series(input: CreateSeriesInput) -> ExternResult<SeriesState> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if !SERIES_LENGTHS.contains(&input.best_of) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Series must be best of one of {:?}", SERIES_LENGTHS))));
+    }
+    if input.opponent == my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot play a series against yourself".into())));
+    }
+    if !player_exists(&input.opponent)? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Opponent is not a registered player".into())));
+    }
+    let series = MatchSeries {
+        player_1: my_pub_key.clone(),
+        player_2: input.opponent.clone(),
+        best_of: input.best_of,
+        created_at: sys_time()?,
+        status: SeriesStatus::InProgress,
+        winner: None,
+        decided_by: None,
+        wins: vec![],
+    };
+    let series_id = create_entry(&EntryTypes::MatchSeries(series.clone()))?;
+    for player in [&series.player_1, &series.player_2] {
+        create_link(player.clone(), series_id.clone(), LinkTypes::PlayerToSeries, ())?;
+    }
+    advance(series_id.clone(), series_id, series)
+}
+
+/// A series with its games and score.
+#[hdk_extern]
+pub fn get_series(series_id: ActionHash) -> ExternResult<SeriesState> {
+    let (_, series) = get_latest_series_state(&series_id)?;
+    build_state(series_id, series)
+}
+
+/// Every series `player` plays in, newest first.
+#[hdk_extern]
+pub fn get_series_for_player(player: AgentPubKey) -> ExternResult<Vec<SeriesState>> {
+    let links = get_links(GetLinksInputBuilder::try_new(player, LinkTypes::PlayerToSeries)?.build())?;
+    let mut states = Vec::new();
+    for series_id in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        match get_series(series_id.clone()) {
+            Ok(state) => states.push(state),
+            Err(e) => debug!("[match_series] Skipping unreadable series {:?}: {:?}", series_id, e),
+        }
+    }
+    states.sort_by_key(|state| std::cmp::Reverse(state.series.created_at));
+    Ok(states)
+}
+
+/// Records the winner once a player has enough wins, otherwise creates the next game
+/// if its last one is decided and the caller is the next game's player 1. Safe to call
+/// repeatedly; UIs call it when told a series game ended.
+#[hdk_extern]
+pub fn advance_series(series_id: ActionHash) -> ExternResult<SeriesState> {
+    let (previous_action_hash, series) = get_latest_series_state(&series_id)?;
+    advance(series_id, previous_action_hash, series)
+}
+
+/// `advance_series` from a known latest state (`previous_action_hash` is its action).
+fn advance(series_id: ActionHash, previous_action_hash: ActionHash, series: MatchSeries) -> ExternResult<SeriesState> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let mut state = build_state(series_id.clone(), series)?;
+    if state.series.status != SeriesStatus::InProgress || !state.series.is_player(&my_pub_key) {
+        return Ok(state);
+    }
+
+    // 1. A player reached the wins needed: record the result
+    let wins_needed = state.series.wins_needed();
+    let series_winner = if state.player_1_wins >= wins_needed {
+        Some(state.series.player_1.clone())
+    } else if state.player_2_wins >= wins_needed {
+        Some(state.series.player_2.clone())
+    } else {
+        None
+    };
+    if let Some(winner) = series_winner {
+        // The winner's first wins_needed wins; the last of them decided the series
+        let wins: Vec<SeriesWin> = state.games.iter()
+            .filter(|game| game.winner.as_ref() == Some(&winner))
+            .filter_map(|game| Some(SeriesWin { game_number: game.game_number, decided_by: game.decided_by.clone()? }))
+            .take(wins_needed as usize)
+            .collect();
+        let decided_by = wins.last()
+            .map(|win| win.decided_by.clone())
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the deciding game of the series".into())))?;
+        let finished = MatchSeries {
+            status: SeriesStatus::Finished,
+            winner: Some(winner.clone()),
+            decided_by: Some(decided_by),
+            wins,
+            ..state.series.clone()
+        };
+        update_entry(previous_action_hash, &EntryTypes::MatchSeries(finished.clone()))?;
+        state.series = finished;
+        notify(&state, Signal::SeriesOver {
+            series_id,
+            player_1: state.series.player_1.clone(),
+            player_2: state.series.player_2.clone(),
+            winner,
+            player_1_wins: state.player_1_wins,
+            player_2_wins: state.player_2_wins,
+        })?;
+        return Ok(state);
+    }
+
+    // 2. Otherwise the next game, once the last one is over
+    if state.games.last().is_some_and(|game| matches!(game.status, GameStatus::Waiting | GameStatus::InProgress)) {
+        return Ok(state);
+    }
+    let game_number = state.games.len() as u32;
+    let (player_1, player_2) = state.series.players_for_game(game_number);
+    let next_game_id = if player_1 == my_pub_key {
+        let game_id = create_series_game(&series_id, game_number, &player_1, &player_2)?;
+        state.games.push(SeriesGame {
+            game_number,
+            game_id: game_id.clone(),
+            player_1: player_1.clone(),
+            player_2: player_2.clone(),
+            status: GameStatus::Waiting,
+            winner: None,
+            decided_by: None,
+        });
+        Some(game_id)
+    } else {
+        None // The opponent creates it when told, with this update
+    };
+    notify(&state, Signal::SeriesUpdate {
+        series_id,
+        player_1: state.series.player_1.clone(),
+        player_2: state.series.player_2.clone(),
+        player_1_wins: state.player_1_wins,
+        player_2_wins: state.player_2_wins,
+        game_number,
+        next_game_id,
+    })?;
+    Ok(state)
+}
+
+/// Called whenever a game ends. For series games, records the series result or sets up
+/// the next game; failures are only logged, since the game itself has already been
+/// updated and `advance_series` can be retried from the series view.
+pub(crate) fn advance_after_game(game: &Game) {
+    let Some(series_id) = game.series_id.clone() else { return };
+    if let Err(e) = advance_series(series_id.clone()) {
+        warn!("[match_series] Could not advance series {:?}: {:?}", series_id, e);
+    }
+}
+
+/// Whether `agent` plays in the series; used to check relayed series signals.
+pub(crate) fn is_series_player(series_id: &ActionHash, agent: &AgentPubKey) -> ExternResult<bool> {
+    let record = get(series_id.clone(), GetOptions::default())?;
+    let series = record.and_then(|record| record.entry().to_app_option::<MatchSeries>().ok().flatten());
+    Ok(series.is_some_and(|series| series.is_player(agent)))
+}
+
+// --- helpers ---
+
+/// Latest state of a series: the original, or its Finished update (earliest if two raced).
+fn get_latest_series_state(series_id: &ActionHash) -> ExternResult<(ActionHash, MatchSeries)> {
+    let Some(Details::Record(details)) = get_details(series_id.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Series not found: {:?}", series_id))));
+    };
+    let (action_hash, record) = match details.updates.iter().min_by_key(|update| update.action().timestamp()) {
+        Some(update) => {
+            let record = get(update.as_hash().clone(), GetOptions::default())?
+                .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the series update".into())))?;
+            (update.as_hash().clone(), record)
+        }
+        None => (series_id.clone(), details.record),
+    };
+    let series = record
+        .entry()
+        .to_app_option::<MatchSeries>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record is not a MatchSeries entry".into())))?;
+    Ok((action_hash, series))
+}
+
+fn build_state(series_id: ActionHash, series: MatchSeries) -> ExternResult<SeriesState> {
+    let mut links = get_links(GetLinksInputBuilder::try_new(series_id.clone(), LinkTypes::SeriesToGames)?.build())?;
+    links.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.create_link_hash.cmp(&b.create_link_hash)));
+
+    let mut games: Vec<SeriesGame> = Vec::new();
+    for link in links {
+        let (Ok(tag), Some(game_id)) = (SeriesGameTag::from_link_tag(&link.tag), link.target.into_action_hash()) else { continue };
+        // Games are numbered in order; the earliest link for a number is the one that counts
+        if tag.game_number != games.len() as u32 {
+            continue;
+        }
+        let Some(record) = get_latest_game(game_id.clone())? else { continue };
+        let Ok(Some(game)) = record.entry().to_app_option::<Game>() else { continue };
+        let (player_1, player_2) = series.players_for_game(tag.game_number);
+        let winner = match game.game_status {
+            GameStatus::Finished => game.result.and_then(|co_signed| co_signed.result.winner),
+            // Whoever walked away forfeits
+            GameStatus::Abandoned | GameStatus::Canceled => {
+                let author = record.action().author();
+                if *author == player_1 { Some(player_2.clone()) } else if *author == player_2 { Some(player_1.clone()) } else { None }
+            }
+            GameStatus::Waiting | GameStatus::InProgress => None,
+        };
+        let decided_by = winner.as_ref().map(|_| record.action_address().clone());
+        games.push(SeriesGame { game_number: tag.game_number, game_id, player_1, player_2, status: game.game_status, winner, decided_by });
+    }
+
+    let wins = |player: &AgentPubKey| games.iter().filter(|game| game.winner.as_ref() == Some(player)).count() as u32;
+    let (player_1_wins, player_2_wins) = (wins(&series.player_1), wins(&series.player_2));
+    Ok(SeriesState { series_id, series, games, player_1_wins, player_2_wins })
+}
+
+/// Creates a series game with the sides of its number and links it into the series.
+/// Player 2 starts it by joining, exactly like an accepted invitation.
+fn create_series_game(series_id: &ActionHash, game_number: u32, player_1: &AgentPubKey, player_2: &AgentPubKey) -> ExternResult<ActionHash> {
+    let game = Game {
+        player_1: player_1.clone(),
+        player_2: Some(player_2.clone()),
+        game_status: GameStatus::Waiting,
+        created_at: sys_time()?,
+        player_1_paddle: 250,
+        player_2_paddle: 250,
+        ball_x: 400,
+        ball_y: 300,
+        result: None,
+        visibility: GameVisibility::InviteOnly, // The seat is reserved for player 2
+        tournament_id: None,
+        series_id: Some(series_id.clone()),
+    };
+    let game_id = commit_new_game(&game)?;
+    create_link(series_id.clone(), game_id.clone(), LinkTypes::SeriesToGames, SeriesGameTag { game_number }.to_link_tag()?)?;
+    Ok(game_id)
+}
+
+/// Emits a series signal locally and sends it to the other player.
+fn notify(state: &SeriesState, signal: Signal) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    emit_signal(&signal)?;
+    let opponent = if state.series.player_1 == my_pub_key { &state.series.player_2 } else { &state.series.player_1 };
+    for failure in send_signal_to(vec![opponent.clone()], &signal)? {
+        debug!("[match_series] Series signal not delivered to {:?}: {}", failure.agent, failure.reason);
+    }
+    Ok(())
+}
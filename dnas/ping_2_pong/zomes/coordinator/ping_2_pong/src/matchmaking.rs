@@ -0,0 +1,267 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/matchmaking.rs
+// Automatic matchmaking queue.
+//
+// Queued agents are linked from the "matchmaking_queue" anchor. While queued, each
+// client calls `find_match` periodically: it scans the other queued agents, ranks them
+// by win-rate proximity (from Score history) and by the signal latency they recorded
+// in Statistics, and proposes a match to the best candidate via `call_remote`.
+// Once the candidate accepts, the proposer creates the game with both players and
+// sends `Signal::MatchFound` so player 2's UI can join it. Each side leaves the queue
+// only once the game exists, so a failed `create_game` costs neither its place.
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use crate::Signal;
+use crate::signals::{send_signal_to, zome_name};
+use ping_2_pong_integrity::game::GameVisibility;
+use crate::game::{create_game, CreateGameInput};
+use crate::score::get_scores_for_player;
+use crate::utils::{anchor_for, player_exists, is_player_in_ongoing_game};
+
+const QUEUE_ANCHOR: &str = "matchmaking_queue";
+const QUEUE_ENTRY_TTL_SECS: i64 = 600; // Queue links older than this are treated as stale
+const PROFILE_RECENT_GAMES: usize = 20; // Games considered for win rate and latency
+const BASE_WIN_RATE_GAP: f64 = 0.15; // Allowed win-rate difference when first queued
+const WIN_RATE_GAP_PER_MINUTE: f64 = 0.10; // The allowed gap widens the longer an agent waits
+const MAX_MATCH_LATENCY_MS: u32 = 400; // Pairs whose average latency exceeds this are not matched
+const LATENCY_WEIGHT: f64 = 0.25; // Weight of latency relative to win-rate difference
+const MAX_PROPOSALS_PER_SCAN: usize = 3;
+
+/// What matchmaking knows about a player.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatchmakingProfile {
+    pub agent: AgentPubKey,
+    pub games_played: u32,
+    pub win_rate: f64,               // Smoothed: (wins + 1) / (games + 2), so new players start at 0.5
+    pub avg_latency_ms: Option<u32>, // Average client-measured signal latency; None if never recorded
+}
+
+/// Answer to a match proposal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MatchProposalResponse {
+    Accepted,
+    Declined(String),
+}
+
+// Queue links that haven't gone stale.
+fn get_queue_links() -> ExternResult<Vec<Link>> {
+    let now = sys_time()?;
+    let links = get_links(
+        GetLinksInputBuilder::try_new(anchor_for(QUEUE_ANCHOR)?, LinkTypes::MatchmakingQueue)?.build(),
+    )?;
+    Ok(links
+        .into_iter()
+        .filter(|link| now.as_seconds_and_nanos().0 - link.timestamp.as_seconds_and_nanos().0 <= QUEUE_ENTRY_TTL_SECS)
+        .collect())
+}
+
+// An agent's own queue link, if they are queued.
+fn own_queue_link(links: &[Link], me: &AgentPubKey) -> Option<Link> {
+    links
+        .iter()
+        .filter(|link| link.author == *me && link.target.clone().into_agent_pub_key().as_ref() == Some(me))
+        .max_by(|a, b| a.timestamp.cmp(&b.timestamp))
+        .cloned()
+}
+
+// Seconds the agent has been waiting in the queue.
+fn waited_secs(link: &Link) -> ExternResult<i64> {
+    Ok((sys_time()?.as_seconds_and_nanos().0 - link.timestamp.as_seconds_and_nanos().0).max(0))
+}
+
+/// Builds a player's matchmaking profile from their recent Scores and Statistics.
+pub fn get_matchmaking_profile(agent: &AgentPubKey) -> ExternResult<MatchmakingProfile> {
+    let mut scores: Vec<Score> = get_scores_for_player(agent.clone())?
+        .into_iter()
+        .filter_map(|record| record.entry().to_app_option::<Score>().ok().flatten())
+        .collect();
+    scores.sort_by_key(|score| std::cmp::Reverse(score.created_at)); // Most recent first
+    scores.truncate(PROFILE_RECENT_GAMES);
+
+    let games_played = scores.len() as u32;
+    let wins = scores
+        .iter()
+        .filter(|score| score.result.result.winner.as_ref() == Some(agent))
+        .count() as u32;
+    let win_rate = (wins as f64 + 1.0) / (games_played as f64 + 2.0);
+
+    // Statistics the player recorded for those games
+    let mut get_inputs: Vec<GetInput> = Vec::new();
+    for score in &scores {
+        let links = get_links(
+            GetLinksInputBuilder::try_new(score.game_id.clone(), LinkTypes::GameToStatistics)?.build(),
+        )?;
+        get_inputs.extend(
+            links
+                .into_iter()
+                .filter(|link| link.author == *agent)
+                .filter_map(|link| link.target.into_action_hash())
+                .map(|ah| GetInput::new(ah.into(), GetOptions::default())),
+        );
+    }
+    let latencies: Vec<u32> = if get_inputs.is_empty() {
+        vec![]
+    } else {
+        HDK.with(|hdk| hdk.borrow().get(get_inputs))?
+            .into_iter()
+            .flatten()
+            .filter_map(|record| record.entry().to_app_option::<Statistics>().ok().flatten())
+            .map(|statistics| statistics.signal_latency)
+            .collect()
+    };
+    let avg_latency_ms = if latencies.is_empty() {
+        None
+    } else {
+        Some((latencies.iter().map(|l| *l as u64).sum::<u64>() / latencies.len() as u64) as u32)
+    };
+
+    Ok(MatchmakingProfile { agent: agent.clone(), games_played, win_rate, avg_latency_ms })
+}
+
+/// Cost of pairing two players (lower is better), or None if they should not be paired.
+/// The allowed win-rate gap widens with the time `waited_secs` spent in the queue.
+pub fn match_cost(a: &MatchmakingProfile, b: &MatchmakingProfile, waited_secs: i64) -> Option<f64> {
+    let gap = (a.win_rate - b.win_rate).abs();
+    let allowed_gap = (BASE_WIN_RATE_GAP + WIN_RATE_GAP_PER_MINUTE * waited_secs as f64 / 60.0).min(1.0);
+    if gap > allowed_gap {
+        return None;
+    }
+    // Unknown latency counts as neutral: half the acceptable maximum
+    let latency = |profile: &MatchmakingProfile| profile.avg_latency_ms.unwrap_or(MAX_MATCH_LATENCY_MS / 2) as f64;
+    let pair_latency = (latency(a) + latency(b)) / 2.0;
+    if pair_latency > MAX_MATCH_LATENCY_MS as f64 {
+        return None;
+    }
+    Some(gap + LATENCY_WEIGHT * pair_latency / MAX_MATCH_LATENCY_MS as f64)
+}
+
+/// Adds the caller to the matchmaking queue. Re-entering refreshes the queue link.
+#[hdk_extern]
+pub fn enter_queue(_: ()) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if !player_exists(&my_pub_key)? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Register a player profile before entering the queue".into())));
+    }
+    if is_player_in_ongoing_game(&my_pub_key)? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot enter the queue while in an ongoing game".into())));
+    }
+    leave_queue(())?;
+    create_link(anchor_for(QUEUE_ANCHOR)?, my_pub_key, LinkTypes::MatchmakingQueue, ())?;
+    Ok(())
+}
+
+/// Removes the caller from the matchmaking queue.
+#[hdk_extern]
+pub fn leave_queue(_: ()) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let links = get_links(
+        GetLinksInputBuilder::try_new(anchor_for(QUEUE_ANCHOR)?, LinkTypes::MatchmakingQueue)?.build(),
+    )?;
+    for link in links {
+        if link.author == my_pub_key {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+/// Scans the queue and proposes a match to the best candidates.
+/// Returns the original ActionHash of the created game once a candidate accepts.
+/// Only candidates with a greater AgentPubKey are proposed to, so two agents never
+/// propose to each other at the same time.
+#[hdk_extern]
+pub fn find_match(_: ()) -> ExternResult<Option<ActionHash>> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let links = get_queue_links()?;
+    let Some(my_link) = own_queue_link(&links, &my_pub_key) else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Not in the matchmaking queue".into())));
+    };
+    let waited = waited_secs(&my_link)?;
+    let my_profile = get_matchmaking_profile(&my_pub_key)?;
+
+    let mut candidates: Vec<AgentPubKey> = links
+        .iter()
+        .filter(|link| link.author != my_pub_key)
+        .filter_map(|link| link.target.clone().into_agent_pub_key())
+        .filter(|agent| *agent > my_pub_key)
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    let mut ranked: Vec<(f64, AgentPubKey)> = Vec::new();
+    for candidate in candidates {
+        let profile = get_matchmaking_profile(&candidate)?;
+        if let Some(cost) = match_cost(&my_profile, &profile, waited) {
+            ranked.push((cost, candidate));
+        }
+    }
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    for (cost, candidate) in ranked.into_iter().take(MAX_PROPOSALS_PER_SCAN) {
+        debug!("[matchmaking] Proposing match to {:?} (cost {:.3})", candidate, cost);
+        let response = match call_remote(candidate.clone(), zome_name()?, "receive_match_proposal".into(), None, ())? {
+            ZomeCallResponse::Ok(output) => output
+                .decode::<MatchProposalResponse>()
+                .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?,
+            other => {
+                warn!("[matchmaking] Match proposal to {:?} failed: {:?}", candidate, other);
+                continue;
+            }
+        };
+        if let MatchProposalResponse::Declined(reason) = response {
+            debug!("[matchmaking] {:?} declined: {}", candidate, reason);
+            continue;
+        }
+
+        // Both sides accepted: the candidate leaves the queue when MatchFound reaches it
+        let game_record = create_game(CreateGameInput {
+            player_1: my_pub_key.clone(),
+            player_2: Some(candidate.clone()),
+            visibility: GameVisibility::InviteOnly, // The seat is the matched player's
+        })?;
+        leave_queue(())?;
+        let game_id = game_record.action_hashed().hash.clone();
+        let signal = Signal::MatchFound {
+            game_id: game_id.clone(),
+            player_1: my_pub_key.clone(),
+            player_2: candidate.clone(),
+        };
+        emit_signal(&signal)?;
+        for failure in send_signal_to(vec![candidate], &signal)? {
+            warn!("[matchmaking] MatchFound not delivered to {:?}: {}", failure.agent, failure.reason);
+        }
+        return Ok(Some(game_id));
+    }
+    Ok(None)
+}
+
+/// Called remotely by a queued agent proposing a match. Accepts if the caller is
+/// still queued, free to play and an acceptable opponent from this side as well.
+/// The callee stays queued until the proposer's `Signal::MatchFound` arrives, so it
+/// keeps its place if the proposer fails to create the game.
+#[hdk_extern]
+pub fn receive_match_proposal(_: ()) -> ExternResult<MatchProposalResponse> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let proposer = call_info()?.provenance;
+    if proposer == my_pub_key {
+        return Ok(MatchProposalResponse::Declined("Cannot match with yourself".into()));
+    }
+
+    let links = get_queue_links()?;
+    let Some(my_link) = own_queue_link(&links, &my_pub_key) else {
+        return Ok(MatchProposalResponse::Declined("No longer in the queue".into()));
+    };
+    if own_queue_link(&links, &proposer).is_none() {
+        return Ok(MatchProposalResponse::Declined("Proposer is not in the queue".into()));
+    }
+    if is_player_in_ongoing_game(&my_pub_key)? {
+        return Ok(MatchProposalResponse::Declined("Already in an ongoing game".into()));
+    }
+
+    let my_profile = get_matchmaking_profile(&my_pub_key)?;
+    let proposer_profile = get_matchmaking_profile(&proposer)?;
+    if match_cost(&my_profile, &proposer_profile, waited_secs(&my_link)?).is_none() {
+        return Ok(MatchProposalResponse::Declined("Skill or latency too far apart".into()));
+    }
+
+    Ok(MatchProposalResponse::Accepted)
+}
@@ -0,0 +1,374 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/physics.rs
+// Deterministic Pong simulation.
+//
+// All positions and velocities are 16.16 fixed-point integers so that both peers
+// (and any replay) produce bit-identical results from the same paddle inputs.
+// The UI runs the same simulation (ui/src/ping_2_pong/game/physics.ts); change both together.
+// This module is pure (no host calls) so it can be used from zome functions and tests alike.
+use serde::{Deserialize, Serialize};
+
+const FRAC_BITS: u32 = 16;
+
+/// Converts whole pixels to fixed-point.
+pub const fn to_fixed(px: i32) -> i32 {
+    px << FRAC_BITS
+}
+
+/// Converts fixed-point back to whole pixels (rounds towards negative infinity).
+pub const fn to_pixels(value: i32) -> i32 {
+    value >> FRAC_BITS
+}
+
+// Multiplies two fixed-point values.
+fn mul(a: i32, b: i32) -> i32 {
+    ((a as i64 * b as i64) >> FRAC_BITS) as i32
+}
+
+// --- Game constants (pixels unless noted) ---
+pub const CANVAS_WIDTH: i32 = 800;
+pub const CANVAS_HEIGHT: i32 = 600;
+pub const PADDLE_WIDTH: i32 = 10;
+pub const PADDLE_HEIGHT: i32 = 100;
+pub const BALL_RADIUS: i32 = 10;
+pub const SERVE_SPEED: i32 = 5; // pixels per tick on both axes
+pub const MAX_BALL_SPEED: i32 = 30; // pixels per tick, keeps the ball from tunnelling through paddles
+pub const WINNING_SCORE: u32 = 10;
+
+const W: i32 = to_fixed(CANVAS_WIDTH);
+const H: i32 = to_fixed(CANVAS_HEIGHT);
+const PW: i32 = to_fixed(PADDLE_WIDTH);
+const PH: i32 = to_fixed(PADDLE_HEIGHT);
+const R: i32 = to_fixed(BALL_RADIUS);
+const SPEEDUP: i32 = 68_813; // 1.05 in 16.16
+const ANGLE_FACTOR: i32 = 22_938; // 0.35 in 16.16
+
+/// Which side of the table a player is on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerSide {
+    Player1, // Left paddle
+    Player2, // Right paddle
+}
+
+/// Ball position and velocity in fixed-point.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BallState {
+    pub x: i32,
+    pub y: i32,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+/// Paddle positions (top edge, whole pixels) applied for one tick.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaddleInputs {
+    pub paddle_1_y: u32,
+    pub paddle_2_y: u32,
+}
+
+/// Full simulation state at a given tick.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PhysicsState {
+    pub tick: u64,
+    pub ball: BallState,
+    pub paddle_1_y: i32, // fixed-point
+    pub paddle_2_y: i32, // fixed-point
+    pub score1: u32,
+    pub score2: u32,
+}
+
+/// What happened during a single step.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepEvent {
+    None,
+    WallBounce,
+    PaddleHit(PlayerSide),
+    Goal(PlayerSide),     // The side that scored; the ball has been re-served
+    GameOver(PlayerSide), // The side that won; the simulation stops advancing the ball
+}
+
+impl PhysicsState {
+    /// Initial state of a match, matching the default positions written by `create_game`.
+    pub fn new_game(first_serve_toward: PlayerSide) -> Self {
+        let paddle_y = to_fixed(CANVAS_HEIGHT / 2 - PADDLE_HEIGHT / 2);
+        let mut state = PhysicsState {
+            tick: 0,
+            ball: BallState { x: W / 2, y: H / 2, dx: 0, dy: 0 },
+            paddle_1_y: paddle_y,
+            paddle_2_y: paddle_y,
+            score1: 0,
+            score2: 0,
+        };
+        state.serve(first_serve_toward);
+        state
+    }
+
+    /// The winning side, once either player has reached `WINNING_SCORE`.
+    pub fn winner(&self) -> Option<PlayerSide> {
+        if self.score1 >= WINNING_SCORE {
+            Some(PlayerSide::Player1)
+        } else if self.score2 >= WINNING_SCORE {
+            Some(PlayerSide::Player2)
+        } else {
+            None
+        }
+    }
+
+    // Resets the ball to the centre. The vertical direction alternates with the
+    // number of points played so serves are deterministic.
+    fn serve(&mut self, toward: PlayerSide) {
+        let dx = match toward {
+            PlayerSide::Player1 => -to_fixed(SERVE_SPEED),
+            PlayerSide::Player2 => to_fixed(SERVE_SPEED),
+        };
+        let dy = if (self.score1 + self.score2).is_multiple_of(2) { to_fixed(SERVE_SPEED) } else { -to_fixed(SERVE_SPEED) };
+        self.ball = BallState { x: W / 2, y: H / 2, dx, dy };
+    }
+}
+
+// Clamps a paddle's top edge to the canvas and converts it to fixed-point.
+fn paddle_to_fixed(paddle_y: u32) -> i32 {
+    let max = CANVAS_HEIGHT - PADDLE_HEIGHT;
+    to_fixed((paddle_y.min(max as u32)) as i32)
+}
+
+fn clamp_speed(v: i32) -> i32 {
+    v.clamp(-to_fixed(MAX_BALL_SPEED), to_fixed(MAX_BALL_SPEED))
+}
+
+/// Advances the simulation by one tick using the given paddle inputs.
+pub fn step(state: &mut PhysicsState, inputs: PaddleInputs) -> StepEvent {
+    state.tick += 1;
+    state.paddle_1_y = paddle_to_fixed(inputs.paddle_1_y);
+    state.paddle_2_y = paddle_to_fixed(inputs.paddle_2_y);
+
+    if let Some(winner) = state.winner() {
+        return StepEvent::GameOver(winner);
+    }
+
+    let mut event = StepEvent::None;
+    let p1 = state.paddle_1_y;
+    let p2 = state.paddle_2_y;
+    let ball = &mut state.ball;
+
+    // Move ball
+    ball.x += ball.dx;
+    ball.y += ball.dy;
+
+    // Top/bottom walls
+    if ball.y + R > H || ball.y - R < 0 {
+        ball.dy = -ball.dy;
+        ball.y = ball.y.clamp(R, H - R);
+        event = StepEvent::WallBounce;
+    }
+
+    // Paddles
+    if ball.dx < 0 && ball.x - R < PW && ball.x > R && ball.y > p1 && ball.y < p1 + PH {
+        ball.dx = clamp_speed(-mul(ball.dx, SPEEDUP));
+        ball.x = PW + R;
+        ball.dy = clamp_speed(mul(ball.y - (p1 + PH / 2), ANGLE_FACTOR));
+        return StepEvent::PaddleHit(PlayerSide::Player1);
+    } else if ball.dx > 0 && ball.x + R > W - PW && ball.x < W - R && ball.y > p2 && ball.y < p2 + PH {
+        ball.dx = clamp_speed(-mul(ball.dx, SPEEDUP));
+        ball.x = W - PW - R;
+        ball.dy = clamp_speed(mul(ball.y - (p2 + PH / 2), ANGLE_FACTOR));
+        return StepEvent::PaddleHit(PlayerSide::Player2);
+    }
+
+    // Goals: the ball left the table past a paddle
+    let scorer = if ball.x + R < 0 {
+        PlayerSide::Player2
+    } else if ball.x - R > W {
+        PlayerSide::Player1
+    } else {
+        return event;
+    };
+    match scorer {
+        PlayerSide::Player1 => state.score1 += 1,
+        PlayerSide::Player2 => state.score2 += 1,
+    }
+    if let Some(winner) = state.winner() {
+        return StepEvent::GameOver(winner);
+    }
+    // Serve towards the player who conceded
+    let conceded = match scorer {
+        PlayerSide::Player1 => PlayerSide::Player2,
+        PlayerSide::Player2 => PlayerSide::Player1,
+    };
+    state.serve(conceded);
+    StepEvent::Goal(scorer)
+}
+
+/// Runs the simulation forward over a sequence of per-tick inputs.
+pub fn simulate(mut state: PhysicsState, inputs: &[PaddleInputs]) -> PhysicsState {
+    for input in inputs {
+        step(&mut state, *input);
+    }
+    state
+}
+
+/// Returns true if a ball state reported by a peer (whole pixels, as sent in
+/// `Signal::BallUpdate`) is further than `tolerance_px` from the simulated ball.
+pub fn ball_diverges(simulated: &BallState, ball_x: u32, ball_y: u32, ball_dx: i32, ball_dy: i32, tolerance_px: u32) -> bool {
+    let tolerance = tolerance_px as i64;
+    let off = |sim: i32, reported: i64| (to_pixels(sim) as i64 - reported).abs() > tolerance;
+    off(simulated.x, ball_x as i64)
+        || off(simulated.y, ball_y as i64)
+        || off(simulated.dx, ball_dx as i64)
+        || off(simulated.dy, ball_dy as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CENTRED_PADDLES: PaddleInputs = PaddleInputs {
+        paddle_1_y: (CANVAS_HEIGHT / 2 - PADDLE_HEIGHT / 2) as u32,
+        paddle_2_y: (CANVAS_HEIGHT / 2 - PADDLE_HEIGHT / 2) as u32,
+    };
+    // Both paddles at the top, well clear of a ball travelling through the middle
+    const RAISED_PADDLES: PaddleInputs = PaddleInputs { paddle_1_y: 0, paddle_2_y: 0 };
+
+    fn state_with_ball(ball: BallState) -> PhysicsState {
+        PhysicsState { ball, ..PhysicsState::new_game(PlayerSide::Player1) }
+    }
+
+    #[test]
+    fn new_game_serves_from_the_centre() {
+        let state = PhysicsState::new_game(PlayerSide::Player2);
+        assert_eq!(state.ball, BallState { x: W / 2, y: H / 2, dx: to_fixed(SERVE_SPEED), dy: to_fixed(SERVE_SPEED) });
+        assert_eq!(state.winner(), None);
+    }
+
+    #[test]
+    fn ball_bounces_off_the_bottom_wall() {
+        let mut state = state_with_ball(BallState { x: W / 2, y: H - R - to_fixed(2), dx: to_fixed(5), dy: to_fixed(5) });
+        assert_eq!(step(&mut state, CENTRED_PADDLES), StepEvent::WallBounce);
+        assert_eq!(state.ball.y, H - R);
+        assert_eq!(state.ball.dy, -to_fixed(5));
+        assert_eq!(state.ball.dx, to_fixed(5));
+    }
+
+    #[test]
+    fn ball_bounces_off_the_top_wall() {
+        let mut state = state_with_ball(BallState { x: W / 2, y: R + to_fixed(2), dx: to_fixed(5), dy: -to_fixed(5) });
+        assert_eq!(step(&mut state, CENTRED_PADDLES), StepEvent::WallBounce);
+        assert_eq!(state.ball.y, R);
+        assert_eq!(state.ball.dy, to_fixed(5));
+    }
+
+    #[test]
+    fn paddle_hit_reverses_and_speeds_up_the_ball() {
+        // Dead centre of the left paddle: no added angle
+        let mut state = state_with_ball(BallState { x: PW + R + to_fixed(2), y: H / 2, dx: -to_fixed(5), dy: 0 });
+        assert_eq!(step(&mut state, CENTRED_PADDLES), StepEvent::PaddleHit(PlayerSide::Player1));
+        assert_eq!(state.ball.x, PW + R);
+        assert_eq!(state.ball.dx, mul(to_fixed(5), SPEEDUP));
+        assert!(state.ball.dx > to_fixed(5));
+        assert_eq!(state.ball.dy, 0);
+
+        // Below the centre of the right paddle: deflected downwards
+        let mut state = state_with_ball(BallState { x: W - PW - R - to_fixed(2), y: H / 2 + to_fixed(20), dx: to_fixed(5), dy: 0 });
+        assert_eq!(step(&mut state, CENTRED_PADDLES), StepEvent::PaddleHit(PlayerSide::Player2));
+        assert_eq!(state.ball.x, W - PW - R);
+        assert!(state.ball.dx < -to_fixed(5));
+        assert_eq!(state.ball.dy, mul(to_fixed(20), ANGLE_FACTOR));
+    }
+
+    #[test]
+    fn paddle_hit_speed_is_capped() {
+        // 29 px/tick * 1.05 would exceed the cap
+        let mut state = state_with_ball(BallState { x: PW + R + to_fixed(20), y: H / 2, dx: -to_fixed(MAX_BALL_SPEED - 1), dy: 0 });
+        assert_eq!(step(&mut state, CENTRED_PADDLES), StepEvent::PaddleHit(PlayerSide::Player1));
+        assert_eq!(state.ball.dx, to_fixed(MAX_BALL_SPEED));
+    }
+
+    #[test]
+    fn goal_scores_and_reserves_toward_the_conceding_player() {
+        let mut state = state_with_ball(BallState { x: -R + to_fixed(3), y: H / 2, dx: -to_fixed(5), dy: 0 });
+        assert_eq!(step(&mut state, RAISED_PADDLES), StepEvent::Goal(PlayerSide::Player2));
+        assert_eq!((state.score1, state.score2), (0, 1));
+        // One point played, so the serve goes up; Player 1 conceded, so it goes left
+        assert_eq!(state.ball, BallState { x: W / 2, y: H / 2, dx: -to_fixed(SERVE_SPEED), dy: -to_fixed(SERVE_SPEED) });
+
+        let mut state = state_with_ball(BallState { x: W + R - to_fixed(3), y: H / 2, dx: to_fixed(5), dy: 0 });
+        assert_eq!(step(&mut state, RAISED_PADDLES), StepEvent::Goal(PlayerSide::Player1));
+        assert_eq!((state.score1, state.score2), (1, 0));
+        assert_eq!(state.ball.dx, to_fixed(SERVE_SPEED));
+    }
+
+    #[test]
+    fn winning_goal_ends_the_game_and_freezes_the_ball() {
+        let mut state = state_with_ball(BallState { x: W + R - to_fixed(3), y: H / 2, dx: to_fixed(5), dy: 0 });
+        state.score1 = WINNING_SCORE - 1;
+        assert_eq!(step(&mut state, RAISED_PADDLES), StepEvent::GameOver(PlayerSide::Player1));
+        assert_eq!(state.winner(), Some(PlayerSide::Player1));
+
+        let frozen = state.ball;
+        assert_eq!(step(&mut state, RAISED_PADDLES), StepEvent::GameOver(PlayerSide::Player1));
+        assert_eq!(state.ball, frozen);
+        assert_eq!(state.score1, WINNING_SCORE);
+    }
+
+    #[test]
+    fn paddle_inputs_are_clamped_to_the_canvas() {
+        let mut state = PhysicsState::new_game(PlayerSide::Player1);
+        step(&mut state, PaddleInputs { paddle_1_y: u32::MAX, paddle_2_y: 10_000 });
+        assert_eq!(state.paddle_1_y, to_fixed(CANVAS_HEIGHT - PADDLE_HEIGHT));
+        assert_eq!(state.paddle_2_y, to_fixed(CANVAS_HEIGHT - PADDLE_HEIGHT));
+    }
+
+    // Paddles sweeping up and down at different rates, so rallies, bounces and goals all occur
+    fn scripted_inputs(ticks: u32) -> Vec<PaddleInputs> {
+        let max = (CANVAS_HEIGHT - PADDLE_HEIGHT) as u32;
+        (0..ticks)
+            .map(|t| PaddleInputs { paddle_1_y: (t * 7) % max, paddle_2_y: max - (t * 11) % max })
+            .collect()
+    }
+
+    #[test]
+    fn simulate_is_deterministic_for_identical_inputs() {
+        let inputs = scripted_inputs(20_000);
+        let a = simulate(PhysicsState::new_game(PlayerSide::Player2), &inputs);
+        let b = simulate(PhysicsState::new_game(PlayerSide::Player2), &inputs);
+        assert_eq!(a, b);
+        assert_eq!(a.tick, inputs.len() as u64);
+        assert!(a.score1 + a.score2 > 0, "the script should produce goals");
+
+        // Stepping one tick at a time gives the same state as a single simulate call
+        let mut stepped = PhysicsState::new_game(PlayerSide::Player2);
+        for input in &inputs {
+            step(&mut stepped, *input);
+        }
+        assert_eq!(stepped, a);
+    }
+
+    #[test]
+    fn simulate_depends_on_inputs() {
+        let inputs = scripted_inputs(2_000);
+        let still = vec![CENTRED_PADDLES; inputs.len()];
+        let a = simulate(PhysicsState::new_game(PlayerSide::Player2), &inputs);
+        let b = simulate(PhysicsState::new_game(PlayerSide::Player2), &still);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn ball_diverges_at_the_tolerance_edge() {
+        let ball = BallState { x: to_fixed(400), y: to_fixed(300), dx: -to_fixed(5), dy: to_fixed(6) };
+        assert!(!ball_diverges(&ball, 400, 300, -5, 6, 0));
+        // Exactly at the tolerance is still in sync, one pixel past it is not
+        assert!(!ball_diverges(&ball, 403, 297, -8, 9, 3));
+        assert!(ball_diverges(&ball, 404, 300, -5, 6, 3));
+        assert!(ball_diverges(&ball, 400, 296, -5, 6, 3));
+        assert!(ball_diverges(&ball, 400, 300, -1, 6, 3));
+        assert!(ball_diverges(&ball, 400, 300, -5, 10, 3));
+    }
+
+    #[test]
+    fn ball_diverges_compares_whole_pixels() {
+        // 400.5 and -5.5 px truncate towards negative infinity: 400 and -6
+        let ball = BallState { x: to_fixed(400) + (1 << 15), y: to_fixed(300), dx: -to_fixed(5) - (1 << 15), dy: 0 };
+        assert!(!ball_diverges(&ball, 400, 300, -6, 0, 0));
+        assert!(ball_diverges(&ball, 401, 300, -6, 0, 0));
+        assert!(ball_diverges(&ball, 400, 300, -5, 0, 0));
+    }
+}
@@ -0,0 +1,215 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/presence.rs
+// Presence heartbeat.
+//
+// Each agent keeps a single Presence record, updated on every heartbeat, and a single
+// link to its latest version from the "presence" anchor. The link tag carries the
+// status and timestamp, so `get_online_users` answers from one get_links call.
+// Heartbeats also prune a bounded number of other agents' stale links.
+//
+// With `presence_mode: gossip` in the DNA properties, heartbeats are sent to peers as
+// remote signals instead, and the Presence record only serves discovery.
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::presence::{PresenceTag, PRESENCE_PRUNE_AFTER_MS};
+use crate::utils::{anchor_for, is_player_in_ongoing_game};
+use crate::Signal;
+use crate::signals::send_signal_to;
+
+const AWAY_AFTER_MS: u64 = 30_000; // No heartbeat for 30 seconds: shown as Away
+const OFFLINE_AFTER_MS: u64 = 10 * 60 * 1000; // No heartbeat for 10 minutes: not listed
+const MAX_PRUNED_PER_HEARTBEAT: usize = 10; // Bounds the writes a single heartbeat makes
+const MAX_ONLINE_USERS: usize = 200;
+
+/// One agent in the online users list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OnlineUser {
+    pub agent: AgentPubKey,
+    pub status: OnlineStatus,
+    pub last_seen: u64, // Milliseconds since epoch
+}
+
+fn now_ms() -> ExternResult<u64> {
+    sys_time()?.as_millis().try_into()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Timestamp conversion error (i64 to u64): {}", e))))
+}
+
+/// Publishes a heartbeat. `status` is the client's view (Online or Idle, default Online);
+/// InGame is set automatically while the agent is in an 'InProgress' game.
+///
+/// In `PresenceMode::Dht` every heartbeat updates our Presence record. In `PresenceMode::Gossip`
+/// the record is only written on first join (and refreshed before others may prune it);
+/// other heartbeats go to peers as `Signal::PresenceHeartbeat`.
+/// Returns the hash of our latest Presence record.
+#[hdk_extern]
+pub fn publish_presence(status: Option<OnlineStatus>) -> ExternResult<ActionHash> {
+    let agent = agent_info()?.agent_latest_pubkey;
+    let now = now_ms()?;
+    let status = if is_player_in_ongoing_game(&agent)? {
+        OnlineStatus::InGame
+    } else {
+        match status {
+            Some(OnlineStatus::Idle) => OnlineStatus::Idle,
+            _ => OnlineStatus::Online, // Away is derived by readers; InGame is derived above
+        }
+    };
+
+    let presence_anchor_hash = anchor_for("presence")?;
+    let links = get_links(GetLinksInputBuilder::try_new(presence_anchor_hash.clone(), LinkTypes::Presence)?.build())?;
+    let (my_links, other_links): (Vec<Link>, Vec<Link>) = links.into_iter().partition(|link| link.author == agent);
+    let latest_link = my_links.iter().max_by(|a, b| a.timestamp.cmp(&b.timestamp)).cloned();
+
+    if DnaProperties::get()?.presence_mode == PresenceMode::Gossip {
+        let heartbeat = Signal::PresenceHeartbeat { agent: agent.clone(), status: status.clone(), timestamp: now };
+        gossip_to_peers(&other_links, &heartbeat);
+
+        // Keep the DHT record just fresh enough that new peers can find us
+        let refresh_before = now.saturating_sub(PRESENCE_PRUNE_AFTER_MS / 2) as i64;
+        if let Some(link) = latest_link.as_ref().filter(|link| link.timestamp.as_millis() >= refresh_before) {
+            return link.target.clone().into_action_hash()
+                .ok_or(wasm_error!(WasmErrorInner::Guest("Presence link target is not an ActionHash".into())));
+        }
+    }
+
+    write_presence(&agent, status, now, presence_anchor_hash, latest_link, my_links, other_links)
+}
+
+// Updates (or creates) our Presence record, replaces our anchor link and prunes stale links of others.
+fn write_presence(
+    agent: &AgentPubKey,
+    status: OnlineStatus,
+    now: u64,
+    presence_anchor_hash: AnyLinkableHash,
+    latest_link: Option<Link>,
+    my_links: Vec<Link>,
+    other_links: Vec<Link>,
+) -> ExternResult<ActionHash> {
+    let presence = Presence { agent_pubkey: agent.clone(), timestamp: now, status: status.clone() };
+
+    // Update our single Presence record, or create it on the first heartbeat
+    let latest_presence_hash = latest_link.and_then(|link| link.target.into_action_hash());
+    let presence_action_hash = match latest_presence_hash {
+        Some(previous) => update_entry(previous, &presence)?,
+        None => create_entry(&EntryTypes::Presence(presence))?,
+    };
+
+    // Replace our link so the anchor holds one link per agent
+    for link in my_links {
+        delete_link(link.create_link_hash)?;
+    }
+    let tag = PresenceTag { status, timestamp: now };
+    create_link(presence_anchor_hash, presence_action_hash.clone(), LinkTypes::Presence, tag.to_link_tag()?)?;
+
+    // Prune other agents' links that have gone stale
+    let prune_before = now.saturating_sub(PRESENCE_PRUNE_AFTER_MS) as i64;
+    for link in other_links
+        .into_iter()
+        .filter(|link| link.timestamp.as_millis() < prune_before)
+        .take(MAX_PRUNED_PER_HEARTBEAT)
+    {
+        if let Err(e) = delete_link(link.create_link_hash) {
+            warn!("[presence.rs] write_presence: Failed to prune stale presence link: {:?}", e);
+        }
+    }
+
+    Ok(presence_action_hash)
+}
+
+// Sends a presence signal to every agent with a Presence link on the anchor.
+// Fire-and-forget: unreachable peers simply stop appearing in each other's views.
+fn gossip_to_peers(other_links: &[Link], signal: &Signal) {
+    let mut peers: Vec<AgentPubKey> = Vec::new();
+    for link in other_links {
+        if !peers.contains(&link.author) {
+            peers.push(link.author.clone());
+        }
+    }
+    peers.truncate(MAX_ONLINE_USERS);
+    match send_signal_to(peers, signal) {
+        Ok(failures) => debug!("[presence.rs] gossip_to_peers: {} peers unreachable", failures.len()),
+        Err(e) => debug!("[presence.rs] gossip_to_peers: Failed to send: {:?}", e),
+    }
+}
+
+/// Announces that we are leaving (app closed). In gossip mode peers are told directly;
+/// in DHT mode our link is removed so we disappear from `get_online_users` at once.
+#[hdk_extern]
+pub fn leave_presence(_: ()) -> ExternResult<()> {
+    let agent = agent_info()?.agent_latest_pubkey;
+    let presence_anchor_hash = anchor_for("presence")?;
+    let links = get_links(GetLinksInputBuilder::try_new(presence_anchor_hash, LinkTypes::Presence)?.build())?;
+    let (my_links, other_links): (Vec<Link>, Vec<Link>) = links.into_iter().partition(|link| link.author == agent);
+
+    match DnaProperties::get()?.presence_mode {
+        PresenceMode::Gossip => gossip_to_peers(&other_links, &Signal::PresenceLeft { agent }),
+        PresenceMode::Dht => {
+            for link in my_links {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The presence mode configured in the DNA properties, so the UI knows whether to
+/// poll `get_online_users` or build its view from presence signals.
+#[hdk_extern]
+pub fn get_presence_mode(_: ()) -> ExternResult<PresenceMode> {
+    Ok(DnaProperties::get()?.presence_mode)
+}
+
+/// Agents seen in the last 10 minutes with their status and last-seen time, most recent first.
+/// Agents without a heartbeat for 30 seconds are reported as Away.
+#[hdk_extern]
+pub fn get_online_users(_: ()) -> ExternResult<Vec<OnlineUser>> {
+    let presence_anchor_hash = anchor_for("presence")?;
+    let links = get_links(GetLinksInputBuilder::try_new(presence_anchor_hash, LinkTypes::Presence)?.build())?;
+    let now = now_ms()?;
+
+    let mut users: Vec<OnlineUser> = Vec::new();
+    for link in links {
+        let tag = match PresenceTag::from_link_tag(&link.tag) {
+            Ok(tag) => tag,
+            Err(_) => continue, // Legacy link without a tag
+        };
+        let age = now.saturating_sub(tag.timestamp);
+        if age > OFFLINE_AFTER_MS {
+            continue;
+        }
+        let status = if age > AWAY_AFTER_MS { OnlineStatus::Away } else { tag.status };
+        // Keep only the most recent heartbeat per agent (old links may not be deleted yet)
+        match users.iter_mut().find(|user| user.agent == link.author) {
+            Some(user) if user.last_seen >= tag.timestamp => {}
+            Some(user) => { user.status = status; user.last_seen = tag.timestamp; }
+            None => users.push(OnlineUser { agent: link.author, status, last_seen: tag.timestamp }),
+        }
+    }
+
+    users.sort_by_key(|user| std::cmp::Reverse(user.last_seen));
+    users.truncate(MAX_ONLINE_USERS);
+    Ok(users)
+}
+
+/// Agents to deliver live messages to (excluding ourselves): everyone listed by
+/// `get_online_users`, or in gossip mode every agent whose Presence link is not yet
+/// stale, since gossip heartbeats do not refresh the link.
+pub(crate) fn online_agents() -> ExternResult<Vec<AgentPubKey>> {
+    let me = agent_info()?.agent_latest_pubkey;
+    let mut agents: Vec<AgentPubKey> = match DnaProperties::get()?.presence_mode {
+        PresenceMode::Dht => get_online_users(())?.into_iter().map(|user| user.agent).collect(),
+        PresenceMode::Gossip => {
+            let presence_anchor_hash = anchor_for("presence")?;
+            let links = get_links(GetLinksInputBuilder::try_new(presence_anchor_hash, LinkTypes::Presence)?.build())?;
+            let fresh_after = now_ms()?.saturating_sub(PRESENCE_PRUNE_AFTER_MS) as i64;
+            let mut agents: Vec<AgentPubKey> = Vec::new();
+            for link in links.into_iter().filter(|link| link.timestamp.as_millis() >= fresh_after) {
+                if !agents.contains(&link.author) {
+                    agents.push(link.author);
+                }
+            }
+            agents.truncate(MAX_ONLINE_USERS);
+            agents
+        }
+    };
+    agents.retain(|agent| *agent != me);
+    Ok(agents)
+}
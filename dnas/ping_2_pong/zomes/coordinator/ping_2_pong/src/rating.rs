@@ -0,0 +1,241 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/rating.rs
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::GameStatus;
+use ping_2_pong_integrity::glicko2::{self, GameOutcome, Glicko2Rating};
+use crate::player::get_all_player_pubkeys;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RatingLeaderboardEntry {
+    pub player_key: AgentPubKey,
+    pub rating: f64,
+    pub deviation: f64,
+    pub games_played: u32,
+}
+
+// Original Rating create action of a player, if they have one.
+fn get_original_rating_hash(player: &AgentPubKey) -> ExternResult<Option<ActionHash>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(player.clone(), LinkTypes::PlayerToRating)?.build(),
+    )?;
+    // Oldest link wins if a race ever created two ratings
+    Ok(links
+        .into_iter()
+        .min_by(|a, b| a.timestamp.cmp(&b.timestamp))
+        .and_then(|link| link.target.into_action_hash()))
+}
+
+// Every revision of a player's rating, oldest first.
+fn get_rating_revisions(original_rating_hash: &ActionHash) -> ExternResult<Vec<(ActionHash, Rating)>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(original_rating_hash.clone(), LinkTypes::RatingUpdates)?.build(),
+    )?;
+    let get_inputs: Vec<GetInput> = std::iter::once(original_rating_hash.clone())
+        .chain(links.into_iter().filter_map(|link| link.target.into_action_hash()))
+        .map(|ah| GetInput::new(ah.into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+    let mut revisions: Vec<(ActionHash, Rating)> = records
+        .into_iter()
+        .flatten()
+        .filter_map(|record| {
+            let hash = record.action_hashed().hash.clone();
+            match record.entry().to_app_option::<Rating>() {
+                Ok(Some(rating)) => Some((hash, rating)),
+                _ => {
+                    warn!("Skipping Rating revision {:?} that could not be deserialized", hash);
+                    None
+                }
+            }
+        })
+        .collect();
+    revisions.sort_by_key(|(_, rating)| rating.games_played);
+    Ok(revisions)
+}
+
+/// Retrieves the latest Rating record of a player, if they have been rated.
+#[hdk_extern]
+pub fn get_rating(player: AgentPubKey) -> ExternResult<Option<Record>> {
+    let Some(original_rating_hash) = get_original_rating_hash(&player)? else {
+        return Ok(None);
+    };
+    let links = get_links(
+        GetLinksInputBuilder::try_new(original_rating_hash.clone(), LinkTypes::RatingUpdates)?.build(),
+    )?;
+    let latest_rating_hash = match links.into_iter().max_by(|a, b| a.timestamp.cmp(&b.timestamp)) {
+        Some(link) => link.target.into_action_hash()
+            .ok_or(wasm_error!(WasmErrorInner::Guest("RatingUpdates link target is not an ActionHash".to_string())))?,
+        None => original_rating_hash,
+    };
+    get(latest_rating_hash, GetOptions::default())
+}
+
+/// Latest revision of a player's rating, if they have one. Revisions form a single
+/// line (enforced by validation), so the latest is the one with the most games.
+pub(crate) fn get_latest_rating_hash(player: &AgentPubKey) -> ExternResult<Option<ActionHash>> {
+    let Some(original_rating_hash) = get_original_rating_hash(player)? else {
+        return Ok(None);
+    };
+    Ok(get_rating_revisions(&original_rating_hash)?.pop().map(|(hash, _)| hash))
+}
+
+/// Returns the caller's latest Rating record, creating the default rating first if
+/// they have none. Called when a game starts, so that both players have a rating
+/// revision to name in the match result (see match_result.rs).
+#[hdk_extern]
+pub fn ensure_rating(_: ()) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let rating_hash = match get_latest_rating_hash(&my_pub_key)? {
+        Some(hash) => hash,
+        None => {
+            let defaults = Glicko2Rating::default();
+            let initial = Rating {
+                player: my_pub_key.clone(),
+                rating: defaults.rating,
+                deviation: defaults.deviation,
+                volatility: defaults.volatility,
+                games_played: 0,
+                last_result: None,
+                updated_at: sys_time()?,
+            };
+            let hash = create_entry(&EntryTypes::Rating(initial))?;
+            create_link(my_pub_key, hash.clone(), LinkTypes::PlayerToRating, ())?;
+            hash
+        }
+    };
+    get(rating_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the Rating".to_string())))
+}
+
+// Fetches a Rating revision by action hash.
+fn get_rating_revision(rating_hash: &ActionHash) -> ExternResult<Rating> {
+    get(rating_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Rating revision not found".to_string())))?
+        .entry()
+        .to_app_option::<Rating>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid Rating entry".to_string())))
+}
+
+/// Updates the caller's rating with the result of a finished game.
+/// Both players' pre-game ratings are the revisions named in the co-signed result,
+/// so both compute their updates from the same inputs regardless of who goes first,
+/// and validation can recompute the update.
+/// Calling it again for an already rated game returns the existing revision.
+#[hdk_extern]
+pub fn update_rating(game_id: ActionHash) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+
+    // --- Validation ---
+    let (_, game) = crate::game::get_latest_game_state(&game_id)?;
+    if game.game_status != GameStatus::Finished {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only 'Finished' games can be rated".into())));
+    }
+    let Some(co_signed) = game.result.clone() else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Finished game has no co-signed result".into())));
+    };
+    let result = &co_signed.result;
+    let (my_rating_hash, opponent_rating_hash) = if my_pub_key == result.player_1 {
+        (result.player_1_rating.clone(), result.player_2_rating.clone())
+    } else if my_pub_key == result.player_2 {
+        (result.player_2_rating.clone(), result.player_1_rating.clone())
+    } else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only game participants can be rated for a game".into())));
+    };
+    // --- End Validation ---
+
+    let original_rating_hash = get_original_rating_hash(&my_pub_key)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the Rating to update".to_string())))?;
+    let my_revisions = get_rating_revisions(&original_rating_hash)?;
+    let rated_this_game = |rating: &Rating| rating.last_result.as_ref().map(|r| &r.result.game_id) == Some(&game_id);
+    if let Some((hash, _)) = my_revisions.iter().find(|(_, rating)| rated_this_game(rating)) {
+        return get(hash.clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the existing Rating revision".to_string())));
+    }
+    // Validation only accepts an update of the latest revision, which must be the one signed for this game
+    if my_revisions.last().map(|(hash, _)| hash) != Some(&my_rating_hash) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Rating has changed since the match result was signed; this game can no longer be rated".into()
+        )));
+    }
+    let current = get_rating_revision(&my_rating_hash)?;
+    let opponent_rating = get_rating_revision(&opponent_rating_hash)?;
+
+    let score = match &result.winner {
+        Some(winner) if *winner == my_pub_key => GameOutcome::WIN,
+        Some(_) => GameOutcome::LOSS,
+        None => GameOutcome::DRAW,
+    };
+    let updated = glicko2::rate_game(Glicko2Rating::from(&current), Glicko2Rating::from(&opponent_rating), score);
+
+    let updated_rating = Rating {
+        player: my_pub_key,
+        rating: updated.rating,
+        deviation: updated.deviation,
+        volatility: updated.volatility,
+        games_played: current.games_played + 1,
+        last_result: Some(co_signed),
+        updated_at: sys_time()?,
+    };
+    let update_action_hash = update_entry(my_rating_hash, &updated_rating)?;
+    create_link(original_rating_hash, update_action_hash.clone(), LinkTypes::RatingUpdates, ())?;
+
+    get(update_action_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the updated Rating".to_string())))
+}
+
+/// Leaderboard of rated players, highest rating first.
+/// With a season (its Season create action), only games rated during the season count:
+/// each player's rating is where it stood at the end of the season (or now, while it
+/// runs) and players who were not rated during it are left out.
+#[hdk_extern]
+pub fn get_rating_leaderboard(season_id: Option<ActionHash>) -> ExternResult<Vec<RatingLeaderboardEntry>> {
+    let season = match season_id {
+        Some(season_id) => Some(crate::league::get_season(&season_id)?),
+        None => None,
+    };
+    let mut entries: Vec<RatingLeaderboardEntry> = Vec::new();
+    for player_key in get_all_player_pubkeys(())? {
+        if let Some(season) = &season {
+            let Some(original_rating_hash) = get_original_rating_hash(&player_key)? else {
+                continue; // Not rated yet
+            };
+            // Revisions are oldest first; the initial one (no games) is never in a season
+            let in_season: Vec<Rating> = get_rating_revisions(&original_rating_hash)?
+                .into_iter()
+                .map(|(_, rating)| rating)
+                .filter(|rating| rating.games_played > 0 && season.contains(rating.updated_at))
+                .collect();
+            if let Some(rating) = in_season.last() {
+                entries.push(RatingLeaderboardEntry {
+                    player_key,
+                    rating: rating.rating,
+                    deviation: rating.deviation,
+                    games_played: in_season.len() as u32,
+                });
+            }
+            continue;
+        }
+        let Some(record) = get_rating(player_key.clone())? else {
+            continue; // Not rated yet
+        };
+        match record.entry().to_app_option::<Rating>() {
+            Ok(Some(rating)) if rating.games_played > 0 => entries.push(RatingLeaderboardEntry {
+                player_key,
+                rating: rating.rating,
+                deviation: rating.deviation,
+                games_played: rating.games_played,
+            }),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to deserialize Rating for player {:?}: {:?}", player_key, e),
+        }
+    }
+
+    entries.sort_by(|a, b| {
+        b.rating.total_cmp(&a.rating) // Sort by rating descending
+            .then_with(|| a.deviation.total_cmp(&b.deviation)) // Then by certainty
+            .then_with(|| a.player_key.cmp(&b.player_key)) // Then by player_key for consistent tie-breaking
+    });
+    Ok(entries)
+}
@@ -0,0 +1,386 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/replay.rs
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::match_replay::ReplayEvent;
+use ping_2_pong_integrity::match_replay_validation::MAX_REPLAY_EVENTS;
+use crate::physics::{self, BallState, PaddleInputs, PhysicsState, PlayerSide};
+
+/// A timeline event as recorded by the UI, with absolute ticks.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RecordedEvent {
+    Input { tick: u64, side: PlayerSide, paddle_y: u32 },
+    BallReset { tick: u64, x: u32, y: u32, dx: i32, dy: i32 },
+    Goal { tick: u64, scorer: PlayerSide },
+}
+
+impl RecordedEvent {
+    fn tick(&self) -> u64 {
+        match self {
+            RecordedEvent::Input { tick, .. } | RecordedEvent::BallReset { tick, .. } | RecordedEvent::Goal { tick, .. } => *tick,
+        }
+    }
+}
+
+/// Input structure for the `create_match_replay` function.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateMatchReplayInput {
+    pub game_id: ActionHash,
+    pub result: CoSignedMatchResult,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Reconstructed state of a single replay frame.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayFrame {
+    pub tick: u64,
+    pub ball: BallState, // fixed-point, see physics.rs
+    pub paddle_1_y: u32,
+    pub paddle_2_y: u32,
+    pub score1: u32,
+    pub score2: u32,
+}
+
+/// Compresses recorded events: sorts them by tick, drops paddle inputs that repeat
+/// the previous value for that side, and delta-encodes the ticks.
+/// Returns the encoded events and the final tick.
+pub fn encode_events(mut events: Vec<RecordedEvent>) -> ExternResult<(Vec<ReplayEvent>, u64)> {
+    events.sort_by_key(|event| event.tick()); // Stable: keeps recording order within a tick
+    let mut encoded = Vec::with_capacity(events.len());
+    let mut last_tick: u64 = 0;
+    let mut last_input: [Option<u32>; 2] = [None, None];
+    for event in events {
+        if let RecordedEvent::Input { side, paddle_y, .. } = &event {
+            let slot = &mut last_input[(*side == PlayerSide::Player2) as usize];
+            if *slot == Some(*paddle_y) {
+                continue;
+            }
+            *slot = Some(*paddle_y);
+        }
+        let tick = event.tick();
+        let dt = u32::try_from(tick - last_tick)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Replay gap between events is too large".into())))?;
+        last_tick = tick;
+        encoded.push(match event {
+            RecordedEvent::Input { side, paddle_y, .. } => ReplayEvent::Input { dt, player_2: side == PlayerSide::Player2, paddle_y },
+            RecordedEvent::BallReset { x, y, dx, dy, .. } => ReplayEvent::BallReset { dt, x, y, dx, dy },
+            RecordedEvent::Goal { scorer, .. } => ReplayEvent::Goal { dt, player_2: scorer == PlayerSide::Player2 },
+        });
+    }
+    Ok((encoded, last_tick))
+}
+
+/// Reconstructs `count` frames of a replay, starting at `from_tick`, with the deterministic physics.
+/// The simulation always runs from tick 0 (it cannot be resumed mid-game), but only the
+/// requested frames are kept. Inputs take effect in the step of their tick; goals and ball
+/// resets record the state after it. Those are authoritative: they re-sync the simulation,
+/// so a recording that disagrees with `physics.rs` cannot drift for more than a point.
+pub fn decode_replay(replay: &MatchReplay, from_tick: u64, count: u32) -> Vec<ReplayFrame> {
+    let mut state = PhysicsState::new_game(PlayerSide::Player2);
+    let mut inputs = PaddleInputs { paddle_1_y: 250, paddle_2_y: 250 };
+    let (mut score1, mut score2) = (0u32, 0u32);
+    let end_tick = from_tick.saturating_add(count as u64).min(replay.final_tick.saturating_add(1)); // Exclusive
+    let mut frames = Vec::with_capacity(end_tick.saturating_sub(from_tick) as usize);
+    let mut events = replay.events.iter().peekable();
+    let mut next_event_tick = events.peek().map(|event| event.dt() as u64);
+    let mut due = Vec::new();
+
+    for tick in 0..end_tick {
+        due.clear();
+        while next_event_tick == Some(tick) {
+            let Some(event) = events.next() else { break };
+            due.push(event);
+            next_event_tick = events.peek().map(|next| tick + next.dt() as u64);
+        }
+
+        for event in &due {
+            match event {
+                ReplayEvent::Input { player_2: false, paddle_y, .. } => inputs.paddle_1_y = *paddle_y,
+                ReplayEvent::Input { player_2: true, paddle_y, .. } => inputs.paddle_2_y = *paddle_y,
+                _ => {}
+            }
+        }
+        if tick > 0 {
+            physics::step(&mut state, inputs);
+        }
+        for event in &due {
+            match event {
+                ReplayEvent::BallReset { x, y, dx, dy, .. } => {
+                    state.ball = BallState {
+                        x: physics::to_fixed(*x as i32),
+                        y: physics::to_fixed(*y as i32),
+                        dx: physics::to_fixed(*dx),
+                        dy: physics::to_fixed(*dy),
+                    };
+                }
+                ReplayEvent::Goal { player_2: false, .. } => score1 += 1,
+                ReplayEvent::Goal { player_2: true, .. } => score2 += 1,
+                ReplayEvent::Input { .. } => {}
+            }
+        }
+        // Scores only change through recorded goals
+        state.score1 = score1;
+        state.score2 = score2;
+
+        if tick < from_tick {
+            continue;
+        }
+        frames.push(ReplayFrame {
+            tick,
+            ball: state.ball,
+            paddle_1_y: inputs.paddle_1_y,
+            paddle_2_y: inputs.paddle_2_y,
+            score1,
+            score2,
+        });
+    }
+    frames
+}
+
+/// Commits the replay of a finished game and links it from the game hash.
+#[hdk_extern]
+pub fn create_match_replay(input: CreateMatchReplayInput) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if my_pub_key != input.result.result.player_1 && my_pub_key != input.result.result.player_2 {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only game participants can record a replay".into())));
+    }
+    if input.result.result.game_id != input.game_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Match result is for a different game".into())));
+    }
+    crate::match_result::check_co_signed_result(&input.result)?;
+    if input.events.len() > MAX_REPLAY_EVENTS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Replay has too many events (max {})", MAX_REPLAY_EVENTS))));
+    }
+    if get_replay(input.game_id.clone())?.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest("A replay has already been recorded for this game".into())));
+    }
+
+    let (events, final_tick) = encode_events(input.events)?;
+    let replay = MatchReplay {
+        game_id: input.game_id.clone(),
+        result: input.result,
+        final_tick,
+        events,
+        created_at: sys_time()?,
+    };
+    let replay_action_hash = create_entry(&EntryTypes::MatchReplay(replay))?;
+    create_link(input.game_id.clone(), replay_action_hash.clone(), LinkTypes::GameToReplay, ())?;
+
+    let record = get(replay_action_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created MatchReplay".to_string())))?;
+    Ok(record)
+}
+
+/// Retrieves the replay record linked from a game, if one was recorded.
+#[hdk_extern]
+pub fn get_replay(game_id: ActionHash) -> ExternResult<Option<Record>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(game_id, LinkTypes::GameToReplay)?.build(),
+    )?;
+    // Oldest link wins if more than one replay was ever linked
+    let Some(link) = links.into_iter().min_by(|a, b| a.timestamp.cmp(&b.timestamp)) else {
+        return Ok(None);
+    };
+    let Some(replay_hash) = link.target.into_action_hash() else {
+        return Err(wasm_error!(WasmErrorInner::Guest("GameToReplay link target is not an ActionHash".to_string())));
+    };
+    get(replay_hash, GetOptions::default())
+}
+
+/// Input structure for the `get_replay_frames` function.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetReplayFramesInput {
+    pub game_id: ActionHash,
+    pub from_tick: u64,
+    pub count: u32, // Capped at MAX_REPLAY_FRAMES_PER_PAGE
+}
+
+/// One page of decoded replay frames.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReplayFramesPage {
+    pub final_tick: u64, // Last tick of the whole replay; request pages until past it
+    pub frames: Vec<ReplayFrame>,
+}
+
+/// Largest page `get_replay_frames` returns: one minute at 60 ticks per second.
+pub const MAX_REPLAY_FRAMES_PER_PAGE: u32 = 3_600;
+
+/// Decodes a page of a game's replay into frame-by-frame state for rewatching.
+#[hdk_extern]
+pub fn get_replay_frames(input: GetReplayFramesInput) -> ExternResult<Option<ReplayFramesPage>> {
+    let Some(record) = get_replay(input.game_id)? else {
+        return Ok(None);
+    };
+    let replay = record
+        .entry()
+        .to_app_option::<MatchReplay>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid MatchReplay entry".into())))?;
+    let count = input.count.min(MAX_REPLAY_FRAMES_PER_PAGE);
+    Ok(Some(ReplayFramesPage {
+        final_tick: replay.final_tick,
+        frames: decode_replay(&replay, input.from_tick, count),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{to_fixed, to_pixels, StepEvent};
+
+    fn input(tick: u64, side: PlayerSide, paddle_y: u32) -> RecordedEvent {
+        RecordedEvent::Input { tick, side, paddle_y }
+    }
+
+    fn replay_of(events: Vec<RecordedEvent>) -> MatchReplay {
+        let (events, final_tick) = encode_events(events).unwrap();
+        let hash = ActionHash::from_raw_36(vec![0; 36]);
+        let agent = AgentPubKey::from_raw_36(vec![0; 36]);
+        let result = MatchResult {
+            game_id: hash.clone(),
+            join_action: hash.clone(),
+            player_1: agent.clone(),
+            player_2: agent,
+            score1: 0,
+            score2: 0,
+            winner: None,
+            player_1_rating: hash.clone(),
+            player_2_rating: hash.clone(),
+        };
+        MatchReplay {
+            game_id: hash,
+            result: CoSignedMatchResult { result, player_1_signature: Signature([0; 64]), player_2_signature: Signature([0; 64]) },
+            final_tick,
+            events,
+            created_at: Timestamp::from_micros(0),
+        }
+    }
+
+    #[test]
+    fn encode_drops_inputs_that_repeat_the_previous_value_for_a_side() {
+        let (encoded, final_tick) = encode_events(vec![
+            input(1, PlayerSide::Player1, 100),
+            input(2, PlayerSide::Player1, 100), // Repeat: dropped
+            input(3, PlayerSide::Player2, 100), // Same value on the other side: kept
+            input(4, PlayerSide::Player1, 120),
+            input(5, PlayerSide::Player1, 100), // Changed back: kept
+        ])
+        .unwrap();
+        assert_eq!(encoded, vec![
+            ReplayEvent::Input { dt: 1, player_2: false, paddle_y: 100 },
+            ReplayEvent::Input { dt: 2, player_2: true, paddle_y: 100 },
+            ReplayEvent::Input { dt: 1, player_2: false, paddle_y: 120 },
+            ReplayEvent::Input { dt: 1, player_2: false, paddle_y: 100 },
+        ]);
+        assert_eq!(final_tick, 5);
+    }
+
+    #[test]
+    fn encode_sorts_events_and_delta_encodes_their_ticks() {
+        let (encoded, final_tick) = encode_events(vec![
+            RecordedEvent::Goal { tick: 900, scorer: PlayerSide::Player2 },
+            input(10, PlayerSide::Player1, 50),
+            RecordedEvent::BallReset { tick: 900, x: 400, y: 300, dx: -5, dy: -5 },
+            input(10, PlayerSide::Player2, 60),
+            input(70_000, PlayerSide::Player2, 80),
+        ])
+        .unwrap();
+        assert_eq!(encoded.iter().map(ReplayEvent::dt).collect::<Vec<_>>(), vec![10, 0, 890, 0, 69_100]);
+        // Recording order is kept within a tick
+        assert!(matches!(encoded[2], ReplayEvent::Goal { player_2: true, .. }));
+        assert!(matches!(encoded[3], ReplayEvent::BallReset { x: 400, y: 300, dx: -5, dy: -5, .. }));
+
+        // Summing the deltas gives back the recorded ticks
+        let ticks: Vec<u64> = encoded
+            .iter()
+            .scan(0u64, |tick, event| {
+                *tick += event.dt() as u64;
+                Some(*tick)
+            })
+            .collect();
+        assert_eq!(ticks, vec![10, 10, 900, 900, 70_000]);
+        assert_eq!(final_tick, 70_000);
+    }
+
+    #[test]
+    fn encode_rejects_a_gap_wider_than_u32() {
+        let widest = u32::MAX as u64;
+        assert!(encode_events(vec![input(1, PlayerSide::Player1, 0), input(1 + widest, PlayerSide::Player1, 10)]).is_ok());
+        assert!(encode_events(vec![input(1, PlayerSide::Player1, 0), input(2 + widest, PlayerSide::Player1, 10)]).is_err());
+    }
+
+    #[test]
+    fn decode_follows_the_simulation_across_a_goal_and_ball_reset() {
+        // Both paddles at the top: the opening serve goes down and right, past Player 2
+        let paddles_at = |tick: u64, goal_tick: Option<u64>| PaddleInputs {
+            paddle_1_y: if goal_tick.is_some_and(|goal| tick >= goal + 20) { 10 } else { 0 },
+            paddle_2_y: 0,
+        };
+        let mut state = PhysicsState::new_game(PlayerSide::Player2);
+        let mut expected = vec![state.clone()];
+        let goal_tick = loop {
+            let inputs = paddles_at(state.tick + 1, None);
+            let event = physics::step(&mut state, inputs);
+            expected.push(state.clone());
+            if let StepEvent::Goal(scorer) = event {
+                assert_eq!(scorer, PlayerSide::Player1);
+                break state.tick;
+            }
+        };
+        for _ in 0..20 {
+            let inputs = paddles_at(state.tick + 1, Some(goal_tick));
+            physics::step(&mut state, inputs);
+            expected.push(state.clone());
+        }
+
+        // What Player 1's UI records for the same game
+        let served = expected[goal_tick as usize].ball;
+        let replay = replay_of(vec![
+            input(1, PlayerSide::Player1, 0),
+            input(1, PlayerSide::Player2, 0),
+            RecordedEvent::Goal { tick: goal_tick, scorer: PlayerSide::Player1 },
+            RecordedEvent::BallReset {
+                tick: goal_tick,
+                x: to_pixels(served.x) as u32,
+                y: to_pixels(served.y) as u32,
+                dx: to_pixels(served.dx),
+                dy: to_pixels(served.dy),
+            },
+            input(goal_tick + 20, PlayerSide::Player1, 10),
+        ]);
+
+        let frames = decode_replay(&replay, 0, MAX_REPLAY_FRAMES_PER_PAGE);
+        assert_eq!(frames.len(), expected.len());
+        for (frame, state) in frames.iter().zip(&expected) {
+            assert_eq!(frame.tick, state.tick);
+            assert_eq!(frame.ball, state.ball, "ball at tick {}", frame.tick);
+            assert_eq!((frame.score1, frame.score2), (state.score1, state.score2), "score at tick {}", frame.tick);
+        }
+        // One point played: re-served towards Player 2, upwards
+        let goal = &frames[goal_tick as usize];
+        assert_eq!((goal.score1, goal.score2), (1, 0));
+        assert_eq!(goal.ball, BallState { x: to_fixed(400), y: to_fixed(300), dx: to_fixed(5), dy: -to_fixed(5) });
+
+        // A page from the middle matches the same frames
+        let page = decode_replay(&replay, goal_tick - 2, 5);
+        assert_eq!(page, frames[goal_tick as usize - 2..goal_tick as usize + 3].to_vec());
+    }
+
+    #[test]
+    fn recorded_goals_and_ball_resets_override_the_simulation() {
+        // A point the simulation did not score, re-served from an arbitrary spot
+        let replay = replay_of(vec![
+            RecordedEvent::Goal { tick: 10, scorer: PlayerSide::Player2 },
+            RecordedEvent::BallReset { tick: 10, x: 100, y: 200, dx: -3, dy: 2 },
+            input(12, PlayerSide::Player1, 300),
+        ]);
+        let frames = decode_replay(&replay, 9, 100);
+        assert_eq!(frames.iter().map(|frame| frame.tick).collect::<Vec<_>>(), vec![9, 10, 11, 12]);
+        assert_eq!((frames[0].score1, frames[0].score2), (0, 0));
+        assert_eq!((frames[1].score1, frames[1].score2), (0, 1));
+        assert_eq!(frames[1].ball, BallState { x: to_fixed(100), y: to_fixed(200), dx: to_fixed(-3), dy: to_fixed(2) });
+        // The simulation carries on from the reset
+        assert_eq!(frames[2].ball, BallState { x: to_fixed(97), y: to_fixed(202), dx: to_fixed(-3), dy: to_fixed(2) });
+        assert_eq!((frames[3].paddle_1_y, frames[3].paddle_2_y), (300, 250));
+        assert_eq!((frames[3].score1, frames[3].score2), (0, 1));
+    }
+}
@@ -12,7 +12,7 @@ const MAX_POINTS: u32 = 10000; // Keep high for flexibility, game logic enforces
 pub struct CreateScoreInput {
     pub game_id: ActionHash,
     pub player: AgentPubKey,
-    pub player_points: u32,
+    pub result: CoSignedMatchResult, // Final result signed by both players; points are taken from it
 }
 
 #[hdk_extern]
@@ -73,22 +73,35 @@ pub fn create_score(input: CreateScoreInput) -> ExternResult<Record> {
         )));
     }
 
-    // Ensure caller is one of the players in the game? Or allow anyone to record score?
-    // Let's allow anyone for now, assuming UI calls this after game ends for both players.
-    // let my_pub_key = agent_info()?.agent_latest_pubkey;
-    // if my_pub_key != game_for_validation.player_1 && game_for_validation.player_2.as_ref() != Some(&my_pub_key) {
-    //     return Err(wasm_error!(WasmErrorInner::Guest("Only game participants can record the score".into())));
-    // }
+    // The score must come from the result both players signed, and match the one the game finished with.
+    crate::match_result::check_co_signed_result(&input.result)?;
+    if input.result.result.game_id != input.game_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Match result is for a different game".into())));
+    }
+    if game_for_validation.result.as_ref() != Some(&input.result) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Match result does not match the result the game finished with".into())));
+    }
+    let player_points = if input.player == input.result.result.player_1 {
+        input.result.result.score1
+    } else {
+        input.result.result.score2
+    };
+
+    // Each player records their own score (enforced by the integrity zome as well).
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if my_pub_key != input.player {
+        return Err(wasm_error!(WasmErrorInner::Guest("Players can only record their own score".into())));
+    }
 
 
     // Validate that the score points are within a reasonable range.
-    if input.player_points > MAX_POINTS { // MAX_POINTS is high, maybe check against game win condition?
-        warn!("Score points {} exceed MAX_POINTS {}", input.player_points, MAX_POINTS);
+    if player_points > MAX_POINTS { // MAX_POINTS is high, maybe check against game win condition?
+        warn!("Score points {} exceed MAX_POINTS {}", player_points, MAX_POINTS);
         // Allow high scores for now, UI/game logic should enforce game rules like first to 10.
         // return Err(wasm_error!(WasmErrorInner::Guest("Player points exceed the maximum allowed".into())));
     }
-     if input.player_points > 100 { // Add a more reasonable sanity check
-         warn!("Recorded score {} seems high.", input.player_points);
+     if player_points > 100 { // Add a more reasonable sanity check
+         warn!("Recorded score {} seems high.", player_points);
      }
      // --- End Validation ---
 
@@ -97,8 +110,9 @@ pub fn create_score(input: CreateScoreInput) -> ExternResult<Record> {
     let score_to_create = Score {
         game_id: input.game_id.clone(),
         player: input.player.clone(),
-        player_points: input.player_points,
+        player_points,
         created_at: sys_time()?,
+        result: input.result.clone(),
     };
     let score_action_hash = match create_entry(&EntryTypes::Score(score_to_create)) {
         Ok(hash) => {
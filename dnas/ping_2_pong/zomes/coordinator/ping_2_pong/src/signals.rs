@@ -1,16 +1,36 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/signals.rs
 use hdk::prelude::*;
-use crate::{Signal, Game};
+use crate::{Signal, CoSignedMatchResult};
+use crate::physics::{self, PaddleInputs, PhysicsState};
 
 /// ───────────────────────── init helper ─────────────────────────
+/// Functions other agents call on us. The grant is unrestricted, so each of them
+/// checks its caller (`call_info().provenance`) itself.
+const REMOTE_CALLABLE_FUNCTIONS: [&str; 4] = [
+    "receive_remote_signal",
+    "receive_match_proposal", // see matchmaking.rs
+    "receive_direct_message", // see direct_messages.rs
+    "recv_remote_signal",     // spectator relays, see spectate.rs
+];
+
+/// How many times `send_signal_to` tries an agent that could not be reached.
+const SIGNAL_SEND_ATTEMPTS: usize = 2;
+
+/// Name of this coordinator zome, as installed (used for every remote call).
+pub(crate) fn zome_name() -> ExternResult<ZomeName> {
+    Ok(zome_info()?.name)
+}
+
+/// Called from `init`: lets any agent call the functions in `REMOTE_CALLABLE_FUNCTIONS`.
 pub fn grant_remote_signal_cap() -> ExternResult<()> {
+    let zome_name = zome_name()?;
     create_cap_grant(CapGrantEntry {
         tag: "remote-signal".into(),
         access: CapAccess::Unrestricted,
         functions: GrantedFunctions::Listed(
-            vec![("ping_2_pong".into(), "receive_remote_signal".into())]
-                .into_iter()
-                .map(Into::into)
+            REMOTE_CALLABLE_FUNCTIONS
+                .iter()
+                .map(|function| (zome_name.clone(), FunctionName::from(*function)))
                 .collect(),
         ),
     })?;
@@ -18,9 +38,114 @@ pub fn grant_remote_signal_cap() -> ExternResult<()> {
 }
 
 /// ──────────────────────── local re-emit ───────────────────────
+/// Any agent may call this (the grant is unrestricted), so the signal is only
+/// re-emitted to our UI once the caller is shown to be entitled to send it.
 #[hdk_extern]
 pub fn receive_remote_signal(signal: Signal) -> ExternResult<()> {
-    emit_signal(&signal)
+    let caller = call_info()?.provenance;
+    if let Err(reason) = verify_remote_signal(&signal, &caller)? {
+        warn!("Rejected remote signal from {:?}: {}", caller, reason);
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Rejected signal: {}", reason))));
+    }
+    emit_signal(&signal)?;
+    // Root spectator relays pass gameplay signals on down the relay tree
+    crate::spectate::forward_to_children(&signal)
+}
+
+/// Checks a remote signal against its caller: every agent named as the sender must be
+/// the caller, and game signals must come from a player of that game.
+/// Returns `Err(reason)` for spoofed signals.
+fn verify_remote_signal(signal: &Signal, caller: &AgentPubKey) -> ExternResult<Result<(), String>> {
+    let same = |agent: &AgentPubKey, role: &str| -> Result<(), String> {
+        if agent == caller { Ok(()) } else { Err(format!("{} is not the caller", role)) }
+    };
+    Ok(match signal {
+        // Only ever emitted locally by post_commit
+        Signal::LinkCreated { .. } | Signal::LinkDeleted { .. }
+        | Signal::EntryCreated { .. } | Signal::EntryUpdated { .. } | Signal::EntryDeleted { .. } => {
+            Err("Local signals cannot be sent remotely".into())
+        }
+
+        Signal::GlobalChatMessage(payload) => same(&payload.sender, "Chat sender"),
+        Signal::GlobalChatMessageDeleted { action_hash } => {
+            match get(action_hash.clone(), GetOptions::default())? {
+                Some(record) if record.action().author() == caller => Ok(()),
+                Some(_) => Err("Only the sender can delete a chat message".into()),
+                None => Err("Deleted chat message not found".into()),
+            }
+        }
+        Signal::DirectMessage { sender, .. } => same(sender, "Direct message sender"),
+        Signal::DirectMessageDelivered { recipient, .. } => same(recipient, "Receipt recipient"),
+        Signal::PresenceHeartbeat { agent, .. } | Signal::PresenceLeft { agent } => same(agent, "Presence agent"),
+
+        // The game was just created or joined and may not have reached us yet,
+        // so these only check the caller's role.
+        Signal::GameInvitation { inviter, .. } => same(inviter, "Inviter"),
+        Signal::InvitationDeclined { invitee, .. } => same(invitee, "Invitee"),
+        Signal::InvitationCancelled { inviter, .. } => same(inviter, "Inviter"),
+        Signal::MatchFound { player_1, .. } => same(player_1, "Match proposer"),
+        Signal::GameStarted { game_id, player_1, player_2 } => {
+            same(player_2, "Joining player").and(check_player_1(game_id, player_1)?)
+        }
+        // Bracket games are created by the organiser or by one of their players
+        Signal::TournamentMatchReady { tournament_id, player_1, player_2, .. } => {
+            if player_1 == caller || player_2 == caller || crate::tournament::is_tournament_organiser(tournament_id, caller)? {
+                Ok(())
+            } else {
+                Err("Caller cannot create matches in this tournament".into())
+            }
+        }
+        // Only a fixture's player 1 creates its game
+        Signal::LeagueFixtureReady { player_1, .. } => same(player_1, "Fixture player 1"),
+        // Either player advances a series
+        Signal::SeriesUpdate { series_id, .. } | Signal::SeriesOver { series_id, .. } => {
+            if crate::match_series::is_series_player(series_id, caller)? {
+                Ok(())
+            } else {
+                Err("Caller does not play in this series".into())
+            }
+        }
+
+        Signal::PaddleUpdate { game_id, player, .. }
+        | Signal::PaddleInput { game_id, player, .. }
+        | Signal::InputAck { game_id, player, .. } => same(player, "Player").and(check_participant(game_id, caller)?),
+        Signal::GameChatMessage { game_id, sender, .. } => same(sender, "Chat sender").and(check_participant(game_id, caller)?),
+        Signal::GameAbandoned { game_id, abandoned_by_player } => {
+            same(abandoned_by_player, "Abandoning player").and(check_participant(game_id, caller)?)
+        }
+        Signal::MatchResultSigned { game_id, result, signer, signature } => {
+            same(signer, "Signer")
+                .and(check_participant(game_id, caller)?)
+                .and(if verify_signature(signer.clone(), signature.clone(), result)? {
+                    Ok(())
+                } else {
+                    Err("Invalid result signature".into())
+                })
+        }
+        // Player 1 runs the ball simulation
+        Signal::BallUpdate { game_id, .. } => check_player_1(game_id, caller)?,
+        Signal::ScoreUpdate { game_id, .. } | Signal::GameOver { game_id, .. } => check_participant(game_id, caller)?,
+    })
+}
+
+// These run for every gameplay signal, so they read the players locally when they can
+// (see `get_game_players_local_first`).
+fn check_participant(game_id: &ActionHash, agent: &AgentPubKey) -> ExternResult<Result<(), String>> {
+    let (player_1, player_2) = crate::game::get_game_players_local_first(game_id, agent)?;
+    if player_1 == *agent || player_2.as_ref() == Some(agent) {
+        Ok(Ok(()))
+    } else {
+        Ok(Err("Caller is not a player of this game".into()))
+    }
+}
+
+fn check_player_1(game_id: &ActionHash, agent: &AgentPubKey) -> ExternResult<Result<(), String>> {
+    let (player_1, _) = crate::game::get_game_players_local_first(game_id, agent)?;
+    if player_1 == *agent {
+        Ok(Ok(()))
+    } else {
+        Ok(Err("Agent is not player 1 of this game".into()))
+    }
 }
 
 /// ─────────────────────── payload structs ──────────────────────
@@ -30,9 +155,27 @@ pub struct PaddleUpdatePayload {
     pub paddle_y: u32,
 }
 
+/// Paddle input for a specific simulation tick.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaddleInputPayload {
+    pub game_id:  ActionHash,
+    pub tick:     u64,
+    pub paddle_y: u32,
+}
+
+/// Acknowledges all of the opponent's inputs up to `ack_tick`; the `ack_tick` input took
+/// effect in our own simulation from `applied_tick`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputAckPayload {
+    pub game_id:      ActionHash,
+    pub ack_tick:     u64,
+    pub applied_tick: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BallUpdatePayload {
     pub game_id: ActionHash,
+    pub tick:    u64, // Player 1's simulation tick the ball was sampled at
     pub ball_x:  u32,
     pub ball_y:  u32,
     pub ball_dx: i32,
@@ -52,61 +195,117 @@ pub struct GameAbandonedPayload {
     pub game_id: ActionHash,
 }
 
-/// Return the `Record` at the *tip* of an update chain
-fn latest_record(original: &ActionHash) -> ExternResult<Record> {
-    let mut current = original.clone();
-
-    loop {
-        let details = get_details(current.clone(), GetOptions::default())?
-            .ok_or(wasm_error!("Game details not found"))?;
+/// Input for `verify_ball_state`: a known state, the paddle inputs applied since,
+/// and the ball state a peer reported afterwards.
+/// `inputs` holds one entry per tick from `from_state.tick + 1` up to `reported.tick`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifyBallStateInput {
+    pub from_state: PhysicsState,
+    pub inputs: Vec<PaddleInputs>,
+    pub reported: BallUpdatePayload,
+    pub tolerance_px: u32,
+}
 
-        match details {
-            Details::Record(rec) => {
-                // Any further updates?
-                if let Some(update) = rec.updates.last() {
-                    current = update.action_address().clone();
-                } else {
-                    return Ok(rec.record);
-                }
-            }
-            // Only `Record` details make sense for an ActionHash.
-            _ => return Err(wasm_error!("Unexpected details variant")),
-        }
-    }
+/// Output of `verify_ball_state`. `simulated` is the exact state at the reported tick,
+/// which the caller can verify the next report from.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BallVerification {
+    pub consistent: bool,
+    pub simulated: PhysicsState,
 }
 
+/// Maximum pixel difference tolerated by default.
+pub const DEFAULT_BALL_TOLERANCE_PX: u32 = 2;
+
 /// ───────────────────── broadcast helper ──────────────────────
-fn broadcast_to_opponents(game_id: &ActionHash, signal: &Signal) -> ExternResult<()> {
-    // 1. load the *latest* Game entry
-    let record = latest_record(game_id)?;
-    let game: Game = record
-        .entry()
-        .to_app_option::<Game>()
-        .map_err(|e| wasm_error!(e.to_string()))?
-        .ok_or(wasm_error!("Malformed Game entry"))?;
+/// Sends a game signal to the other player and, for gameplay signals, to the root spectator relays.
+pub(crate) fn broadcast_to_opponents(game_id: &ActionHash, signal: &Signal) -> ExternResult<()> {
+    // 1. load the players, locally when we hold the game (this runs every frame)
+    let me = agent_info()?.agent_latest_pubkey;
+    let (player_1, player_2) = crate::game::get_game_players_local_first(game_id, &me)?;
 
     // 2. build recipient list (everyone except me)
-    let me = agent_info()?.agent_latest_pubkey;
-    let recipients = [&Some(game.player_1.clone()), &game.player_2]
+    let mut recipients = [&Some(player_1), &player_2]
         .iter()
         .filter_map(|o| o.as_ref())
         .filter(|pk| **pk != me)
         .cloned()
         .collect::<Vec<_>>();
 
-    // 3. fire-and-forget
-    for agent in recipients {
-        let _ = call_remote(
-            agent,
-            "ping_2_pong",               // zome
-            "receive_remote_signal".into(),
-            None,                        // no cap secret
-            signal,              // payload
-        );
+    // 2b. spectators get the signals they need to draw the match, through the
+    //     root relays of the spectator relay tree (see spectate.rs)
+    let is_spectator_signal = crate::spectate::spectator_signal_game_id(signal).is_some();
+    if is_spectator_signal {
+        for relay in crate::spectate::root_relays(game_id)? {
+            if relay != me && !recipients.contains(&relay) {
+                recipients.push(relay);
+            }
+        }
+    }
+
+    // 3. deliver; gameplay signals are superseded quickly, so failures are only logged
+    let failures = send_signal_to(recipients, signal)?;
+    for failure in &failures {
+        debug!("Signal for game {:?} not delivered to {:?}: {}", game_id, failure.agent, failure.reason);
+    }
+
+    // 4. failover: send straight to the children of root relays we could not reach
+    if is_spectator_signal && !failures.is_empty() {
+        let unreachable: Vec<AgentPubKey> = failures.into_iter().map(|failure| failure.agent).collect();
+        let adopted = crate::spectate::children_of_relays(game_id, &unreachable)?;
+        if !adopted.is_empty() {
+            for failure in send_signal_to(adopted, signal)? {
+                debug!("Signal for game {:?} not delivered to {:?}: {}", game_id, failure.agent, failure.reason);
+            }
+        }
     }
     Ok(())
 }
 
+/// A recipient a signal could not be delivered to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeliveryFailure {
+    pub agent:  AgentPubKey,
+    pub reason: String,
+}
+
+/// Sends `signal` to every agent's `receive_remote_signal`. All agents are called in one
+/// batched host call, so the remote calls run concurrently instead of one after another;
+/// agents that could not be reached are tried again. Returns the agents that did not get it.
+pub(crate) fn send_signal_to(agents: Vec<AgentPubKey>, signal: &Signal) -> ExternResult<Vec<DeliveryFailure>> {
+    let zome_name = zome_name()?;
+    let payload = ExternIO::encode(signal.clone()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+    let mut pending = agents;
+    let mut failures: Vec<DeliveryFailure> = Vec::new();
+    for attempt in 1..=SIGNAL_SEND_ATTEMPTS {
+        if pending.is_empty() {
+            break;
+        }
+        let calls: Vec<Call> = pending
+            .iter()
+            .map(|agent| Call::new(
+                CallTarget::NetworkAgent(agent.clone()),
+                zome_name.clone(),
+                "receive_remote_signal".into(),
+                None,                        // no cap secret
+                payload.clone(),
+            ))
+            .collect();
+        let responses = HDK.with(|hdk| hdk.borrow().call(calls))?;
+        let mut retry: Vec<AgentPubKey> = Vec::new();
+        for (agent, response) in pending.into_iter().zip(responses) {
+            match response {
+                ZomeCallResponse::Ok(_) => {}
+                // Network errors are often transient; rejections (e.g. spoof checks) are not
+                ZomeCallResponse::NetworkError(_) if attempt < SIGNAL_SEND_ATTEMPTS => retry.push(agent),
+                other => failures.push(DeliveryFailure { agent, reason: format!("{:?}", other) }),
+            }
+        }
+        pending = retry;
+    }
+    Ok(failures)
+}
+
 /// ───────────────────── externs used by UI ────────────────────
 #[hdk_extern]
 pub fn send_paddle_update(payload: PaddleUpdatePayload) -> ExternResult<()> {
@@ -119,6 +318,33 @@ pub fn send_paddle_update(payload: PaddleUpdatePayload) -> ExternResult<()> {
     broadcast_to_opponents(&payload.game_id, &signal)
 }
 
+/// Sends a tick-stamped paddle input. Receivers apply only the newest tick from the opponent
+/// as it arrives; nothing is rolled back. Player 2 also keeps Player 1's inputs by tick, late
+/// ones included, to re-simulate Player 1's ball (see `verify_ball_state`).
+#[hdk_extern]
+pub fn send_paddle_input(payload: PaddleInputPayload) -> ExternResult<()> {
+    let signal = Signal::PaddleInput {
+        game_id:  payload.game_id.clone(),
+        player:   agent_info()?.agent_latest_pubkey,
+        tick:     payload.tick,
+        paddle_y: payload.paddle_y,
+    };
+    emit_signal(&signal)?;
+    broadcast_to_opponents(&payload.game_id, &signal)
+}
+
+/// Tells the opponent which of their inputs have arrived, so unacknowledged ones can be resent.
+#[hdk_extern]
+pub fn send_input_ack(payload: InputAckPayload) -> ExternResult<()> {
+    let signal = Signal::InputAck {
+        game_id:      payload.game_id.clone(),
+        player:       agent_info()?.agent_latest_pubkey,
+        ack_tick:     payload.ack_tick,
+        applied_tick: payload.applied_tick,
+    };
+    broadcast_to_opponents(&payload.game_id, &signal)
+}
+
 #[hdk_extern]
 pub fn send_game_abandoned_signal(payload: GameAbandonedPayload) -> ExternResult<()> {
     let abandoned_by_player = agent_info()?.agent_latest_pubkey;
@@ -133,10 +359,34 @@ pub fn send_game_abandoned_signal(payload: GameAbandonedPayload) -> ExternResult
     broadcast_to_opponents(&payload.game_id, &signal)
 }
 
+/// Re-simulates the ball from `from_state` and checks the peer's reported ball against it.
+/// `consistent` is `true` if the report matches the deterministic simulation.
+#[hdk_extern]
+pub fn verify_ball_state(input: VerifyBallStateInput) -> ExternResult<BallVerification> {
+    if input.from_state.tick + input.inputs.len() as u64 != input.reported.tick {
+        return Err(wasm_error!(WasmErrorInner::Guest("Inputs do not span from the known state to the reported tick".into())));
+    }
+    let simulated = physics::simulate(input.from_state, &input.inputs);
+    let tolerance = input.tolerance_px.max(DEFAULT_BALL_TOLERANCE_PX);
+    let diverges = physics::ball_diverges(
+        &simulated.ball,
+        input.reported.ball_x,
+        input.reported.ball_y,
+        input.reported.ball_dx,
+        input.reported.ball_dy,
+        tolerance,
+    );
+    if diverges {
+        warn!("Reported ball state for game {:?} diverges from simulation at tick {}", input.reported.game_id, simulated.tick);
+    }
+    Ok(BallVerification { consistent: !diverges, simulated })
+}
+
 #[hdk_extern]
 pub fn send_ball_update(payload: BallUpdatePayload) -> ExternResult<()> {
     let signal = Signal::BallUpdate {
         game_id: payload.game_id.clone(),
+        tick:    payload.tick,
         ball_x:  payload.ball_x,
         ball_y:  payload.ball_y,
         ball_dx: payload.ball_dx,
@@ -158,14 +408,17 @@ pub fn send_score_update(payload: GameOverPayload) -> ExternResult<()> {
     broadcast_to_opponents(&payload.game_id, &signal)
 }
 
+/// Announces the end of a game. Only a result signed by both players is relayed.
 #[hdk_extern]
-pub fn send_game_over(payload: GameOverPayload) -> ExternResult<()> {
+pub fn send_game_over(co_signed: CoSignedMatchResult) -> ExternResult<()> {
+    crate::match_result::check_co_signed_result(&co_signed)?;
+    let result = co_signed.result;
     let signal = Signal::GameOver {
-        game_id: payload.game_id.clone(),
-        winner:  payload.winner.clone(),
-        score1:  payload.score1,
-        score2:  payload.score2,
+        game_id: result.game_id.clone(),
+        winner:  result.winner.clone(),
+        score1:  result.score1,
+        score2:  result.score2,
     };
     emit_signal(&signal)?;
-    broadcast_to_opponents(&payload.game_id, &signal)
+    broadcast_to_opponents(&result.game_id, &signal)
 }
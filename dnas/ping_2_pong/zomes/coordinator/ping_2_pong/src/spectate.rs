@@ -0,0 +1,196 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/spectate.rs
+// Watching games in progress.
+//
+// A spectator registers with a GameToSpectators link from the game to their key.
+// The gameplay signals the players already exchange (paddles, ball, score, game
+// over) reach spectators through a relay tree, so watching needs no extra calls
+// from the players' UIs and a popular match does not cost the players more:
+//
+// - Spectators are laid out in join order (oldest GameToSpectators link first).
+//   The first ROOT_RELAYS of them are sent to by the players, via
+//   `broadcast_to_opponents` (see signals.rs). Every spectator then forwards to
+//   the next RELAY_FANOUT spectators below it (`children`), so each player sends at
+//   most ROOT_RELAYS extra signals per tick however many agents are watching.
+// - Relays forward with `send_remote_signal`, which does not wait for the
+//   receiver: the players' call only waits for the root relays, never for the
+//   tree below them. A lost spectator frame is superseded by the next one.
+// - Failover: a root relay the players cannot reach is skipped and its children
+//   are sent to directly. Lower in the tree, a spectator that stops receiving
+//   calls `rejoin_as_spectator`, which moves it to the end of the join order and
+//   so under a different relay.
+// - Layout cache: the per-frame path never goes to the network for the tree. It
+//   reads the GameToSpectators links this node already holds (`GetStrategy::Local`);
+//   the players' and spectators' UIs call `refresh_relay_layout` every few seconds,
+//   which fetches the links from the network and so keeps the local copy current.
+//   Joining, leaving and rejoining fetch them as well.
+// - A spectator accepts a relayed signal only from the players or from its own
+//   parent in the tree as it has the layout cached.
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::GameStatus;
+use crate::Signal;
+use crate::game::{get_latest_game_state, get_game_players_local_first};
+
+/// Spectators the players send to directly.
+const ROOT_RELAYS: usize = 2;
+/// Spectators each relay forwards to.
+const RELAY_FANOUT: usize = 4;
+
+/// Registers the caller as a spectator of an InProgress game they are not playing in.
+#[hdk_extern]
+pub fn join_as_spectator(game_id: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let (_, game) = get_latest_game_state(&game_id)?;
+    if game.player_1 == my_pub_key || game.player_2.as_ref() == Some(&my_pub_key) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Players cannot spectate their own game".into())));
+    }
+    if game.game_status != GameStatus::InProgress {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Can only spectate games in progress, this one is {:?}", game.game_status
+        ))));
+    }
+    if spectators_of(&game_id, GetStrategy::Network)?.contains(&my_pub_key) {
+        return Ok(()); // Already watching
+    }
+    create_link(game_id, my_pub_key, LinkTypes::GameToSpectators, ())?;
+    Ok(())
+}
+
+/// Stops the caller receiving gameplay signals for the game.
+#[hdk_extern]
+pub fn leave_spectating(game_id: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let links = get_links(GetLinksInputBuilder::try_new(game_id, LinkTypes::GameToSpectators)?.build())?;
+    for link in links {
+        if link.target.clone().into_agent_pub_key().as_ref() == Some(&my_pub_key) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+/// Failover for a spectator whose relay stopped forwarding: re-registers the caller,
+/// which moves them to the end of the join order and so under a different relay.
+#[hdk_extern]
+pub fn rejoin_as_spectator(game_id: ActionHash) -> ExternResult<()> {
+    leave_spectating(game_id.clone())?;
+    join_as_spectator(game_id)
+}
+
+/// Agents currently registered as spectators of the game, in join order.
+#[hdk_extern]
+pub fn get_spectators(game_id: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    spectators_of(&game_id, GetStrategy::Network)
+}
+
+/// Fetches the game's relay layout from the network so the per-frame relay path,
+/// which only reads what this node holds, sees spectators that joined or left since.
+/// Called periodically by the players' and spectators' UIs. Returns the spectators.
+#[hdk_extern]
+pub fn refresh_relay_layout(game_id: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    spectators_of(&game_id, GetStrategy::Network)
+}
+
+/// Spectators in join order (oldest link first), the order the relay tree is laid out in.
+/// `GetStrategy::Local` reads the layout as last fetched instead of going to the network.
+pub(crate) fn spectators_of(game_id: &ActionHash, strategy: GetStrategy) -> ExternResult<Vec<AgentPubKey>> {
+    let mut links = get_links(
+        GetLinksInputBuilder::try_new(game_id.clone(), LinkTypes::GameToSpectators)?.get_options(strategy).build(),
+    )?;
+    // Ties are broken by hash so every agent builds the same tree from the same links
+    links.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.create_link_hash.cmp(&b.create_link_hash)));
+    let mut spectators: Vec<AgentPubKey> = Vec::new();
+    for spectator in links.into_iter().filter_map(|link| link.target.into_agent_pub_key()) {
+        if !spectators.contains(&spectator) {
+            spectators.push(spectator);
+        }
+    }
+    Ok(spectators)
+}
+
+/// Positions in `spectators` that the node at `position` sends to: the root relays
+/// for the players (`None`), otherwise that spectator's own RELAY_FANOUT children.
+fn children(position: Option<usize>, spectators: &[AgentPubKey]) -> Vec<AgentPubKey> {
+    let start = match position {
+        None => 0,
+        Some(i) => ROOT_RELAYS + i * RELAY_FANOUT,
+    };
+    let len = if position.is_none() { ROOT_RELAYS } else { RELAY_FANOUT };
+    spectators.iter().skip(start).take(len).cloned().collect()
+}
+
+/// The spectator that forwards to the spectator at `position`, or `None` for the root
+/// relays, which the players send to themselves.
+fn parent(position: usize, spectators: &[AgentPubKey]) -> Option<&AgentPubKey> {
+    let below_roots = position.checked_sub(ROOT_RELAYS)?;
+    spectators.get(below_roots / RELAY_FANOUT)
+}
+
+/// The spectators a player sends a gameplay signal to: the root relays.
+pub(crate) fn root_relays(game_id: &ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    Ok(children(None, &spectators_of(game_id, GetStrategy::Local)?))
+}
+
+/// Failover for the players: the children of root relays that could not be reached.
+pub(crate) fn children_of_relays(game_id: &ActionHash, relays: &[AgentPubKey]) -> ExternResult<Vec<AgentPubKey>> {
+    let spectators = spectators_of(game_id, GetStrategy::Local)?;
+    Ok(relays
+        .iter()
+        .filter_map(|relay| spectators.iter().position(|s| s == relay))
+        .flat_map(|position| children(Some(position), &spectators))
+        .collect())
+}
+
+/// Called after a gameplay signal reached us: if we are a spectator of its game,
+/// pass it on to our children without waiting for them.
+pub(crate) fn forward_to_children(signal: &Signal) -> ExternResult<()> {
+    let Some(game_id) = spectator_signal_game_id(signal) else { return Ok(()) };
+    let me = agent_info()?.agent_latest_pubkey;
+    let spectators = spectators_of(game_id, GetStrategy::Local)?;
+    let Some(position) = spectators.iter().position(|s| *s == me) else {
+        return Ok(()); // Not a relay (e.g. a player)
+    };
+    let targets = children(Some(position), &spectators);
+    if !targets.is_empty() {
+        send_remote_signal(signal.clone(), targets)?;
+    }
+    Ok(())
+}
+
+/// Receives a gameplay signal forwarded by a relay (sent with `send_remote_signal`).
+/// It is accepted from the game's players and from our parent in the relay tree only,
+/// both read without going to the network. A dishonest parent could still show its
+/// subtree a wrong picture of the match; nothing a spectator sees is ever written to the DHT.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: Signal) -> ExternResult<()> {
+    let caller = call_info()?.provenance;
+    let Some(game_id) = spectator_signal_game_id(&signal) else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only gameplay signals are relayed".into())));
+    };
+    let me = agent_info()?.agent_latest_pubkey;
+    let spectators = spectators_of(game_id, GetStrategy::Local)?;
+    let my_parent = spectators.iter().position(|s| *s == me).and_then(|position| parent(position, &spectators));
+    // Checked second, as it goes to the network for an agent not known locally as a player
+    if my_parent != Some(&caller) {
+        let (player_1, player_2) = get_game_players_local_first(game_id, &caller)?;
+        if player_1 != caller && player_2.as_ref() != Some(&caller) {
+            return Err(wasm_error!(WasmErrorInner::Guest("Relayed signal is not from a player or our relay".into())));
+        }
+    }
+    emit_signal(&signal)?;
+    forward_to_children(&signal)
+}
+
+/// Game of a gameplay signal a spectator needs to draw the match. Input acks,
+/// result signatures and chat stay between the players (None).
+pub(crate) fn spectator_signal_game_id(signal: &Signal) -> Option<&ActionHash> {
+    match signal {
+        Signal::PaddleUpdate { game_id, .. }
+        | Signal::PaddleInput { game_id, .. }
+        | Signal::BallUpdate { game_id, .. }
+        | Signal::ScoreUpdate { game_id, .. }
+        | Signal::GameOver { game_id, .. }
+        | Signal::GameAbandoned { game_id, .. } => Some(game_id),
+        _ => None,
+    }
+}
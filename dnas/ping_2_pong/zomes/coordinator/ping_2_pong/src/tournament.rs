@@ -0,0 +1,558 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/coordinator/ping_2_pong/src/tournament.rs
+// Single-elimination tournaments.
+//
+// - The organiser creates a Tournament entry, linked from the "tournaments" anchor.
+// - Players register with a TournamentToPlayers link from the tournament to their key.
+// - `start_tournament` fixes the seeds in the Tournament entry and creates the first
+//   round's bracket games. The bracket is a power of two: when the field is smaller,
+//   the best seeds get a bye into the second round.
+// - Bracket matches are ordinary invite-only Games (player 2 joins, as for an
+//   invitation) carrying the tournament's id, linked from the tournament with a
+//   TournamentMatchTag saying where they sit in the bracket.
+// - When a bracket game ends, the next match is created as soon as both of its
+//   players are known (`advance_after_game`); the final decides the tournament.
+//   A game abandoned or canceled by one player is won by the other.
+// - Each match is created by exactly one agent, so two games are never raced into the
+//   same slot: the organiser creates the first round when starting the tournament, and
+//   every later match is created by its player 1.
+// - The finished tournament lists the games the champion won in each round, which
+//   validation checks against the bracket layout (see integrity tournament.rs).
+//
+// The bracket is never stored: `get_tournament_bracket` rebuilds it from the seeds and
+// the linked games, so every agent derives the same bracket from the same DHT data.
+use hdk::prelude::*;
+use ping_2_pong_integrity::*;
+use ping_2_pong_integrity::game::{GameStatus, GameVisibility};
+use ping_2_pong_integrity::tournament::{
+    TournamentFormat, TournamentSeeding, TournamentMatchTag, bracket_size, seed_order,
+    MIN_TOURNAMENT_PLAYERS, MAX_TOURNAMENT_PLAYERS, MAX_TOURNAMENT_NAME_LENGTH,
+};
+use crate::Signal;
+use crate::game::{commit_new_game, get_latest_game};
+use crate::utils::{anchor_for, player_exists};
+use crate::signals::send_signal_to;
+
+/// Input for `create_tournament`; the caller is the organiser.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateTournamentInput {
+    pub name: String,
+    pub max_players: u32,
+    #[serde(default)]
+    pub format: TournamentFormat,
+    pub start_time: Timestamp, // The organiser can start the tournament from this time
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StartTournamentInput {
+    pub tournament_id: ActionHash, // Original Tournament create action
+    pub seeding: TournamentSeeding,
+}
+
+/// One match of the bracket. Players are None until the matches feeding into it are decided;
+/// a first-round match with only `player_1` is a bye.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BracketMatch {
+    pub round: u32,
+    pub slot: u32,
+    pub player_1: Option<AgentPubKey>,
+    pub player_2: Option<AgentPubKey>,
+    pub game_id: Option<ActionHash>,    // Original Game create action of the deciding or open game; None before the match is created or after a draw
+    pub winner: Option<AgentPubKey>,
+    pub decided_by: Option<ActionHash>, // Game action that decided the match (None for a bye)
+}
+
+/// A tournament with its bracket, first round first.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TournamentBracket {
+    pub tournament_id: ActionHash,
+    pub tournament: Tournament,
+    pub rounds: Vec<Vec<BracketMatch>>,
+}
+
+/// Creates a tournament open for registration, organised by the caller.
+#[hdk_extern]
+pub fn create_tournament(input: CreateTournamentInput) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let name = input.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_TOURNAMENT_NAME_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Tournament name must be 1 to {} characters", MAX_TOURNAMENT_NAME_LENGTH))));
+    }
+    if input.max_players < MIN_TOURNAMENT_PLAYERS || input.max_players > MAX_TOURNAMENT_PLAYERS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Tournament must allow between {} and {} players", MIN_TOURNAMENT_PLAYERS, MAX_TOURNAMENT_PLAYERS
+        ))));
+    }
+    let created_at = sys_time()?;
+    let tournament = Tournament {
+        name,
+        organiser: my_pub_key,
+        max_players: input.max_players,
+        format: input.format,
+        start_time: input.start_time.max(created_at),
+        created_at,
+        status: TournamentStatus::Registration,
+        seeds: vec![],
+        winner: None,
+        final_game: None,
+        winning_games: vec![],
+    };
+    let tournament_hash = create_entry(&EntryTypes::Tournament(tournament))?;
+    create_link(anchor_for("tournaments")?, tournament_hash.clone(), LinkTypes::AllTournaments, ())?;
+    get(tournament_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find the newly created Tournament".to_string())))
+}
+
+/// Latest state of every tournament, newest first.
+#[hdk_extern]
+pub fn get_tournaments(_: ()) -> ExternResult<Vec<(ActionHash, Tournament)>> {
+    let links = get_links(GetLinksInputBuilder::try_new(anchor_for("tournaments")?, LinkTypes::AllTournaments)?.build())?;
+    let mut tournaments = Vec::new();
+    for tournament_id in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        match get_latest_tournament_state(&tournament_id) {
+            Ok((_, tournament)) => tournaments.push((tournament_id, tournament)),
+            Err(e) => debug!("[tournament] Skipping unreadable tournament {:?}: {:?}", tournament_id, e),
+        }
+    }
+    tournaments.sort_by_key(|(_, tournament)| std::cmp::Reverse(tournament.created_at));
+    Ok(tournaments)
+}
+
+/// Registers the caller for a tournament that is still open and not full.
+#[hdk_extern]
+pub fn register_for_tournament(tournament_id: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let (_, tournament) = get_latest_tournament_state(&tournament_id)?;
+    if tournament.status != TournamentStatus::Registration {
+        return Err(wasm_error!(WasmErrorInner::Guest("Registration for this tournament is closed".into())));
+    }
+    if !player_exists(&my_pub_key)? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Create a player profile before registering".into())));
+    }
+    let players = registered_players(&tournament_id)?;
+    if players.contains(&my_pub_key) {
+        return Ok(()); // Already registered
+    }
+    if players.len() as u32 >= tournament.max_players {
+        return Err(wasm_error!(WasmErrorInner::Guest("This tournament is full".into())));
+    }
+    create_link(tournament_id, my_pub_key, LinkTypes::TournamentToPlayers, ())?;
+    Ok(())
+}
+
+/// Withdraws the caller from a tournament that has not started yet.
+#[hdk_extern]
+pub fn unregister_from_tournament(tournament_id: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let (_, tournament) = get_latest_tournament_state(&tournament_id)?;
+    if tournament.status != TournamentStatus::Registration {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cannot withdraw from a tournament that has started".into())));
+    }
+    let links = get_links(GetLinksInputBuilder::try_new(tournament_id, LinkTypes::TournamentToPlayers)?.build())?;
+    for link in links {
+        if link.target.clone().into_agent_pub_key().as_ref() == Some(&my_pub_key) {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+/// Registered players in registration order.
+#[hdk_extern]
+pub fn get_tournament_players(tournament_id: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    registered_players(&tournament_id)
+}
+
+/// The organiser closes registration, seeds the registered players and creates the
+/// first round's games. Players beyond `max_players` (registrations that raced) are left out.
+#[hdk_extern]
+pub fn start_tournament(input: StartTournamentInput) -> ExternResult<TournamentBracket> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let (previous_action_hash, tournament) = get_latest_tournament_state(&input.tournament_id)?;
+    if tournament.organiser != my_pub_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the organiser can start a tournament".into())));
+    }
+    if tournament.status != TournamentStatus::Registration {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Tournament is already {:?}", tournament.status))));
+    }
+    if sys_time()? < tournament.start_time {
+        return Err(wasm_error!(WasmErrorInner::Guest("Tournament cannot start before its start time".into())));
+    }
+    let mut players = registered_players(&input.tournament_id)?;
+    players.truncate(tournament.max_players as usize);
+    if (players.len() as u32) < MIN_TOURNAMENT_PLAYERS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Tournament needs at least {} registered players to start", MIN_TOURNAMENT_PLAYERS
+        ))));
+    }
+
+    let seeds = match input.seeding {
+        TournamentSeeding::WinRate => seed_by_win_rate(players)?,
+        TournamentSeeding::Random => shuffle(players)?,
+    };
+    let started = Tournament { status: TournamentStatus::InProgress, seeds, ..tournament };
+    let started_action_hash = update_entry(previous_action_hash, &EntryTypes::Tournament(started.clone()))?;
+
+    // The first round is ready as soon as the seeds are fixed
+    advance(input.tournament_id, started_action_hash, started)
+}
+
+/// Rebuilds the bracket of a tournament from its seeds and linked games.
+/// Before the tournament starts there are no seeds and so no rounds.
+#[hdk_extern]
+pub fn get_tournament_bracket(tournament_id: ActionHash) -> ExternResult<TournamentBracket> {
+    let (_, tournament) = get_latest_tournament_state(&tournament_id)?;
+    build_bracket(tournament_id, tournament)
+}
+
+/// Creates every bracket game whose players are both known and that the caller is the
+/// one to create (see `creates_match`), and finishes the tournament once the final is
+/// decided. Safe to call repeatedly; UIs call it when showing a bracket.
+#[hdk_extern]
+pub fn advance_tournament(tournament_id: ActionHash) -> ExternResult<TournamentBracket> {
+    let (previous_action_hash, tournament) = get_latest_tournament_state(&tournament_id)?;
+    advance(tournament_id, previous_action_hash, tournament)
+}
+
+/// `advance_tournament` from a known latest state (`previous_action_hash` is its action).
+fn advance(tournament_id: ActionHash, previous_action_hash: ActionHash, tournament: Tournament) -> ExternResult<TournamentBracket> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if tournament.status != TournamentStatus::InProgress {
+        return build_bracket(tournament_id, tournament);
+    }
+    let is_organiser = tournament.organiser == my_pub_key;
+    let mut bracket = build_bracket(tournament_id.clone(), tournament.clone())?;
+
+    // 1. Create the matches that are ready
+    for bracket_match in bracket.rounds.iter_mut().flatten() {
+        let (Some(player_1), Some(player_2)) = (bracket_match.player_1.clone(), bracket_match.player_2.clone()) else { continue };
+        if bracket_match.game_id.is_some() || !creates_match(bracket_match, &tournament, &my_pub_key) {
+            continue;
+        }
+        let game_id = create_bracket_game(&tournament_id, bracket_match.round, bracket_match.slot, &player_1, &player_2)?;
+        bracket_match.game_id = Some(game_id);
+    }
+
+    // 2. Finish the tournament once the final is decided
+    let final_match = bracket.rounds.last().and_then(|round| round.first());
+    if let Some(BracketMatch { winner: Some(winner), decided_by: Some(final_game), .. }) = final_match {
+        if is_organiser || *winner == my_pub_key {
+            let finished = Tournament {
+                status: TournamentStatus::Finished,
+                winner: Some(winner.clone()),
+                final_game: Some(final_game.clone()),
+                winning_games: winning_games(&bracket, winner),
+                ..tournament
+            };
+            update_entry(previous_action_hash, &EntryTypes::Tournament(finished.clone()))?;
+            bracket.tournament = finished;
+        }
+    }
+    Ok(bracket)
+}
+
+/// The one agent that creates a match: the organiser for the first round (created when
+/// the tournament starts), the match's player 1 afterwards.
+fn creates_match(bracket_match: &BracketMatch, tournament: &Tournament, agent: &AgentPubKey) -> bool {
+    if bracket_match.round == 0 {
+        tournament.organiser == *agent
+    } else {
+        bracket_match.player_1.as_ref() == Some(agent)
+    }
+}
+
+/// The Game actions that decided the matches `champion` won, first round first
+/// (a bye has none), as listed in the finished tournament.
+fn winning_games(bracket: &TournamentBracket, champion: &AgentPubKey) -> Vec<ActionHash> {
+    bracket
+        .rounds
+        .iter()
+        .flatten()
+        .filter(|bracket_match| bracket_match.winner.as_ref() == Some(champion))
+        .filter_map(|bracket_match| bracket_match.decided_by.clone())
+        .collect()
+}
+
+/// Called whenever a game ends. For bracket games, creates the next match if it is
+/// ready; failures are only logged, since the game itself has already been updated
+/// and `advance_tournament` can be retried from the tournament view.
+pub(crate) fn advance_after_game(game: &Game) {
+    let Some(tournament_id) = game.tournament_id.clone() else { return };
+    if let Err(e) = advance_tournament(tournament_id.clone()) {
+        warn!("[tournament] Could not advance tournament {:?}: {:?}", tournament_id, e);
+    }
+}
+
+// --- helpers ---
+
+/// Latest state of a tournament. Its updates form a chain (Registration -> InProgress
+/// -> Finished), each made on the previous one; if two raced, the earliest wins.
+pub(crate) fn get_latest_tournament_state(tournament_id: &ActionHash) -> ExternResult<(ActionHash, Tournament)> {
+    let mut action_hash = tournament_id.clone();
+    let record = loop {
+        let Some(Details::Record(details)) = get_details(action_hash.clone(), GetOptions::default())? else {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!("Tournament not found: {:?}", action_hash))));
+        };
+        match details.updates.iter().min_by_key(|update| update.action().timestamp()) {
+            Some(update) => action_hash = update.as_hash().clone(),
+            None => break details.record,
+        }
+    };
+    let tournament = record
+        .entry()
+        .to_app_option::<Tournament>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record is not a Tournament entry".into())))?;
+    Ok((action_hash, tournament))
+}
+
+fn registered_players(tournament_id: &ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    let mut links = get_links(GetLinksInputBuilder::try_new(tournament_id.clone(), LinkTypes::TournamentToPlayers)?.build())?;
+    links.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.create_link_hash.cmp(&b.create_link_hash)));
+    let mut players: Vec<AgentPubKey> = Vec::new();
+    for player in links.into_iter().filter_map(|link| link.target.into_agent_pub_key()) {
+        if !players.contains(&player) {
+            players.push(player);
+        }
+    }
+    Ok(players)
+}
+
+/// Orders players by the share of their recorded games they won, then by games played.
+/// Players with equal records keep their registration order.
+fn seed_by_win_rate(players: Vec<AgentPubKey>) -> ExternResult<Vec<AgentPubKey>> {
+    let mut records = Vec::with_capacity(players.len());
+    for player in players {
+        let mut games: Vec<ActionHash> = Vec::new();
+        let mut wins = 0u32;
+        for record in crate::score::get_scores_for_player(player.clone())? {
+            let Ok(Some(score)) = record.entry().to_app_option::<Score>() else { continue };
+            if score.player != player || games.contains(&score.game_id) {
+                continue; // One score per game counts
+            }
+            if score.result.result.winner.as_ref() == Some(&player) {
+                wins += 1;
+            }
+            games.push(score.game_id);
+        }
+        let win_rate = if games.is_empty() { 0.0 } else { wins as f64 / games.len() as f64 };
+        records.push((player, win_rate, games.len()));
+    }
+    records.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| b.2.cmp(&a.2)));
+    Ok(records.into_iter().map(|(player, _, _)| player).collect())
+}
+
+/// Fisher-Yates shuffle driven by the host's random bytes.
+fn shuffle(mut players: Vec<AgentPubKey>) -> ExternResult<Vec<AgentPubKey>> {
+    let bytes = random_bytes(4 * players.len() as u32)?;
+    for i in (1..players.len()).rev() {
+        let chunk: [u8; 4] = bytes[4 * i..4 * i + 4].try_into().unwrap_or_default();
+        let j = u32::from_le_bytes(chunk) as usize % (i + 1);
+        players.swap(i, j);
+    }
+    Ok(players)
+}
+
+fn build_bracket(tournament_id: ActionHash, tournament: Tournament) -> ExternResult<TournamentBracket> {
+    if tournament.seeds.is_empty() {
+        return Ok(TournamentBracket { tournament_id, tournament, rounds: vec![] });
+    }
+    let size = bracket_size(tournament.seeds.len());
+    let games = bracket_games(&tournament_id)?;
+    let order = seed_order(size);
+
+    let mut rounds: Vec<Vec<BracketMatch>> = Vec::new();
+    let mut matches_in_round = size / 2;
+    let mut round = 0u32;
+    while matches_in_round >= 1 {
+        let mut matches = Vec::with_capacity(matches_in_round);
+        for slot in 0..matches_in_round {
+            let (player_1, player_2) = match rounds.last() {
+                None => (tournament.seeds.get(order[2 * slot]).cloned(), tournament.seeds.get(order[2 * slot + 1]).cloned()),
+                Some(previous) => (previous[2 * slot].winner.clone(), previous[2 * slot + 1].winner.clone()),
+            };
+            let mut bracket_match = BracketMatch {
+                round, slot: slot as u32, player_1, player_2, game_id: None, winner: None, decided_by: None,
+            };
+            decide_match(&mut bracket_match, &games)?;
+            matches.push(bracket_match);
+        }
+        rounds.push(matches);
+        matches_in_round /= 2;
+        round += 1;
+    }
+    Ok(TournamentBracket { tournament_id, tournament, rounds })
+}
+
+/// Fills in the game and winner of a match from the games linked to the tournament.
+fn decide_match(bracket_match: &mut BracketMatch, games: &[(TournamentMatchTag, ActionHash)]) -> ExternResult<()> {
+    let (player_1, player_2) = match (&bracket_match.player_1, &bracket_match.player_2) {
+        // Byes only occur in the first round, where the better seed is always present
+        (Some(player_1), None) if bracket_match.round == 0 => {
+            bracket_match.winner = Some(player_1.clone());
+            return Ok(());
+        }
+        (Some(player_1), Some(player_2)) => (player_1.clone(), player_2.clone()),
+        _ => return Ok(()), // Waiting for earlier rounds
+    };
+    let tag = TournamentMatchTag { round: bracket_match.round, slot: bracket_match.slot };
+    for (_, game_id) in games.iter().filter(|(game_tag, _)| *game_tag == tag) {
+        let Some(record) = get_latest_game(game_id.clone())? else { continue };
+        let Ok(Some(game)) = record.entry().to_app_option::<Game>() else { continue };
+        // Anyone playing in the tournament could link a game to any slot, so only a game
+        // between the players the bracket expects counts
+        if game.player_1 != player_1 || game.player_2.as_ref() != Some(&player_2) {
+            continue;
+        }
+        let winner = match game.game_status {
+            GameStatus::Finished => game.result.and_then(|co_signed| co_signed.result.winner),
+            // Whoever walked away forfeits
+            GameStatus::Abandoned | GameStatus::Canceled => {
+                let author = record.action().author();
+                if *author == player_1 { Some(player_2.clone()) } else if *author == player_2 { Some(player_1.clone()) } else { None }
+            }
+            GameStatus::Waiting | GameStatus::InProgress => None,
+        };
+        // The first decided game settles the match
+        if winner.is_some() {
+            bracket_match.game_id = Some(game_id.clone());
+            bracket_match.winner = winner;
+            bracket_match.decided_by = Some(record.action_address().clone());
+            break;
+        }
+        // Until then it shows the earliest open game. A game that ended undecided (a draw) is
+        // spent, so once every linked game is spent `advance` creates a replay.
+        let open = matches!(game.game_status, GameStatus::Waiting | GameStatus::InProgress);
+        if open && bracket_match.game_id.is_none() {
+            bracket_match.game_id = Some(game_id.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Games linked to the tournament with their bracket position, earliest link first.
+fn bracket_games(tournament_id: &ActionHash) -> ExternResult<Vec<(TournamentMatchTag, ActionHash)>> {
+    let mut links = get_links(GetLinksInputBuilder::try_new(tournament_id.clone(), LinkTypes::TournamentToGames)?.build())?;
+    links.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.create_link_hash.cmp(&b.create_link_hash)));
+    Ok(links
+        .into_iter()
+        .filter_map(|link| {
+            let tag = TournamentMatchTag::from_link_tag(&link.tag).ok()?;
+            Some((tag, link.target.into_action_hash()?))
+        })
+        .collect())
+}
+
+/// Creates a bracket game between two players, links it into the bracket and tells them.
+/// Player 2 starts the match by joining it, exactly like an accepted invitation.
+fn create_bracket_game(tournament_id: &ActionHash, round: u32, slot: u32, player_1: &AgentPubKey, player_2: &AgentPubKey) -> ExternResult<ActionHash> {
+    let game = Game {
+        player_1: player_1.clone(),
+        player_2: Some(player_2.clone()),
+        game_status: GameStatus::Waiting,
+        created_at: sys_time()?,
+        player_1_paddle: 250,
+        player_2_paddle: 250,
+        ball_x: 400,
+        ball_y: 300,
+        result: None,
+        visibility: GameVisibility::InviteOnly, // The seat is reserved for player 2
+        tournament_id: Some(tournament_id.clone()),
+        series_id: None,
+    };
+    let game_id = commit_new_game(&game)?;
+    create_link(tournament_id.clone(), game_id.clone(), LinkTypes::TournamentToGames, TournamentMatchTag { round, slot }.to_link_tag()?)?;
+
+    let signal = Signal::TournamentMatchReady {
+        tournament_id: tournament_id.clone(),
+        game_id: game_id.clone(),
+        round,
+        player_1: player_1.clone(),
+        player_2: player_2.clone(),
+    };
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let mut recipients = Vec::new();
+    for player in [player_1, player_2] {
+        if *player == my_pub_key {
+            emit_signal(&signal)?;
+        } else {
+            recipients.push(player.clone());
+        }
+    }
+    for failure in send_signal_to(recipients, &signal)? {
+        debug!("[tournament] Match ready not delivered to {:?}: {}", failure.agent, failure.reason);
+    }
+    Ok(game_id)
+}
+
+/// Whether `agent` organises the tournament; used to check relayed tournament signals.
+pub(crate) fn is_tournament_organiser(tournament_id: &ActionHash, agent: &AgentPubKey) -> ExternResult<bool> {
+    let record = get(tournament_id.clone(), GetOptions::default())?;
+    Ok(record.is_some_and(|record| record.action().author() == agent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(n: u8) -> AgentPubKey {
+        AgentPubKey::from_raw_36(vec![n; 36])
+    }
+
+    fn bracket_match(round: u32, player_1: Option<u8>, winner: Option<u8>, decided_by: Option<u8>) -> BracketMatch {
+        BracketMatch {
+            round,
+            slot: 0,
+            player_1: player_1.map(agent),
+            player_2: None,
+            game_id: None,
+            winner: winner.map(agent),
+            decided_by: decided_by.map(|n| ActionHash::from_raw_36(vec![n; 36])),
+        }
+    }
+
+    fn tournament(organiser: u8) -> Tournament {
+        Tournament {
+            name: "Cup".into(),
+            organiser: agent(organiser),
+            max_players: 8,
+            format: TournamentFormat::SingleElimination,
+            start_time: Timestamp::from_micros(0),
+            created_at: Timestamp::from_micros(0),
+            status: TournamentStatus::InProgress,
+            seeds: vec![],
+            winner: None,
+            final_game: None,
+            winning_games: vec![],
+        }
+    }
+
+    #[test]
+    fn first_round_is_created_by_the_organiser_only() {
+        let first_round = bracket_match(0, Some(1), None, None);
+        assert!(creates_match(&first_round, &tournament(9), &agent(9)));
+        assert!(!creates_match(&first_round, &tournament(9), &agent(1)));
+    }
+
+    #[test]
+    fn later_rounds_are_created_by_player_1_only() {
+        let second_round = bracket_match(1, Some(1), None, None);
+        assert!(creates_match(&second_round, &tournament(9), &agent(1)));
+        assert!(!creates_match(&second_round, &tournament(9), &agent(9)));
+        assert!(!creates_match(&bracket_match(1, None, None, None), &tournament(9), &agent(1)));
+    }
+
+    #[test]
+    fn winning_games_skip_byes_and_other_winners() {
+        let bracket = TournamentBracket {
+            tournament_id: ActionHash::from_raw_36(vec![0; 36]),
+            tournament: tournament(9),
+            rounds: vec![
+                vec![bracket_match(0, Some(1), Some(1), None), bracket_match(0, Some(2), Some(3), Some(20))],
+                vec![bracket_match(1, Some(1), Some(1), Some(11))],
+                vec![bracket_match(2, Some(1), Some(1), Some(12))],
+            ],
+        };
+        let expected = vec![ActionHash::from_raw_36(vec![11; 36]), ActionHash::from_raw_36(vec![12; 36])];
+        assert_eq!(winning_games(&bracket, &agent(1)), expected);
+    }
+}
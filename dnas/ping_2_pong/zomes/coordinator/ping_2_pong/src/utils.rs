@@ -12,6 +12,15 @@ pub fn anchor_for(input: &str) -> ExternResult<AnyLinkableHash> {
 }
 
 
+// Day helpers live in the integrity crate so validation can derive bucket anchors too.
+pub use ping_2_pong_integrity::utils::{day_of, format_day, game_bucket_name};
+
+// Daily bucket anchor games are linked from, e.g. "games.2026-10-17".
+pub fn game_bucket_anchor(day: i64) -> ExternResult<AnyLinkableHash> {
+    anchor_for(&game_bucket_name(day))
+}
+
+
 // Helper function to get game hash by game_id (original ActionHash of the game entry).
 pub fn get_game_hash_by_id(game_id: &ActionHash) -> ExternResult<Option<ActionHash>> {
     // Now uses the local `anchor_for` which delegates
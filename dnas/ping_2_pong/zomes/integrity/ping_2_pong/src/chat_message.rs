@@ -0,0 +1,25 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/chat_message.rs
+use hdk::prelude::*;
+use crate::utils::format_day;
+
+const MICROS_PER_HOUR: i64 = 3_600_000_000;
+
+// A global chat message, linked from the hourly chat bucket of its timestamp
+// so history can be read back one bucket at a time.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ChatMessage {
+    pub sender: AgentPubKey, // Must be the author
+    pub content: String,
+    pub timestamp: Timestamp, // Set by the sender's conductor, close to the action timestamp
+}
+
+// Hour number (hours since the Unix epoch, UTC) of a timestamp.
+pub fn hour_of(timestamp: Timestamp) -> i64 {
+    timestamp.as_micros().div_euclid(MICROS_PER_HOUR)
+}
+
+// Name of the hourly chat bucket anchor, e.g. "chat.2026-10-17.09".
+pub fn chat_bucket_name(hour: i64) -> String {
+    format!("chat.{}.{:02}", format_day(hour.div_euclid(24)), hour.rem_euclid(24))
+}
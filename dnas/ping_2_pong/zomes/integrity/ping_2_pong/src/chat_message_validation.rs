@@ -0,0 +1,143 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/chat_message_validation.rs
+use hdk::prelude::*;
+use crate::chat_message::{ChatMessage, chat_bucket_name, hour_of};
+use crate::{Game, UnitEntryTypes};
+
+// Maximum length of a chat message, in characters.
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 500;
+// Rate limit: at most this many messages...
+pub const CHAT_RATE_LIMIT_MESSAGES: usize = 5;
+// ...within this window (milliseconds).
+pub const CHAT_RATE_LIMIT_WINDOW_MS: i64 = 10_000;
+// How many previous source chain actions are scanned for the rate limit.
+const CHAT_RATE_LIMIT_LOOKBACK: u32 = 50;
+
+// Validate creation of a ChatMessage entry.
+pub fn validate_create_chat_message(
+    action: &SignedActionHashed,
+    message: ChatMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Check Author
+    if message.sender != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Chat message sender must be the author".to_string()));
+    }
+
+    // 2. Check Content
+    let content = message.content.trim();
+    if content.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Chat message cannot be empty".to_string()));
+    }
+    if message.content.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!("Chat message is too long (max {} characters)", MAX_CHAT_MESSAGE_LENGTH)));
+    }
+
+    // 3. Check Timestamp plausibility (within 5 minutes of the action)
+    let action_time = action.action().timestamp().as_millis();
+    if (message.timestamp.as_millis() - action_time).abs() > 300_000 {
+        return Ok(ValidateCallbackResult::Invalid("Chat message timestamp is too far from the action timestamp".to_string()));
+    }
+
+    // 4. Check Rate: count the author's recent ChatMessage creates on their source chain
+    let Some(prev_action) = action.action().prev_action() else {
+        return Ok(ValidateCallbackResult::Valid); // Nothing before this action
+    };
+    let chat_entry_def = ScopedEntryDefIndex::try_from(UnitEntryTypes::ChatMessage)?;
+    let activity = must_get_agent_activity(
+        action.action().author().clone(),
+        ChainFilter::new(prev_action.clone()).take(CHAT_RATE_LIMIT_LOOKBACK),
+    )?;
+    let window_start = action_time - CHAT_RATE_LIMIT_WINDOW_MS;
+    let recent_messages = activity
+        .iter()
+        .filter(|item| item.action.action().timestamp().as_millis() > window_start)
+        .filter(|item| match item.action.action() {
+            Action::Create(create) => matches!(
+                &create.entry_type,
+                EntryType::App(def) if def.zome_index == chat_entry_def.zome_index && def.entry_index == chat_entry_def.zome_type
+            ),
+            _ => false,
+        })
+        .count();
+    if recent_messages >= CHAT_RATE_LIMIT_MESSAGES {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Chat rate limit exceeded (max {} messages per {} seconds)",
+            CHAT_RATE_LIMIT_MESSAGES,
+            CHAT_RATE_LIMIT_WINDOW_MS / 1000
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Chat messages cannot be edited.
+pub fn validate_update_chat_message(
+    _action: &SignedActionHashed,
+    _updated_message: ChatMessage,
+    _original_message: &ChatMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Chat messages cannot be updated".to_string()))
+}
+
+// Only the sender can delete a chat message.
+pub fn validate_delete_chat_message(
+    action: &SignedActionHashed,
+    original_message: ChatMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() != original_message.sender {
+        return Ok(ValidateCallbackResult::Invalid("Only the sender can delete a chat message".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a ChatBucketToMessages link: it must come from the hourly bucket of the
+// message's timestamp, and only the sender links their own message.
+pub fn validate_chat_bucket_to_messages_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an EntryHash (the hourly bucket anchor)
+    let Some(base_hash) = create_link.base_address.clone().into_entry_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("ChatBucketToMessages base must be an hourly 'chat.YYYY-MM-DD.HH' anchor hash".into()));
+    };
+    // Target Check: Must be the ActionHash of a ChatMessage
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("ChatBucketToMessages target must be a ChatMessage ActionHash".into()));
+    };
+    let target_record = must_get_valid_record(target_hash)?;
+    let Some(message) = target_record.entry().to_app_option::<ChatMessage>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("ChatBucketToMessages target is not a ChatMessage entry".into()));
+    };
+    // Author Check: Only the sender links their message
+    if create_link.author != message.sender {
+        return Ok(ValidateCallbackResult::Invalid("Author of ChatBucketToMessages link must be the message sender".into()));
+    }
+    // Bucket Check: Must be the bucket of the message timestamp
+    if base_hash != Path::from(chat_bucket_name(hour_of(message.timestamp))).path_entry_hash()? {
+        return Ok(ValidateCallbackResult::Invalid("ChatBucketToMessages base is not the bucket of the message timestamp".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a GameToChatMessages link: the base must be a Game and the target a
+// ChatMessage sent by the link author.
+pub fn validate_game_to_chat_messages_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be a Game ActionHash
+    let Some(game_hash) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToChatMessages base must be a Game ActionHash".into()));
+    };
+    let game_record = must_get_valid_record(game_hash)?;
+    if game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?.is_none() {
+        return Ok(ValidateCallbackResult::Invalid("GameToChatMessages base is not a Game entry".into()));
+    }
+    // Target Check: Must be the ActionHash of a ChatMessage
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToChatMessages target must be a ChatMessage ActionHash".into()));
+    };
+    let target_record = must_get_valid_record(target_hash)?;
+    let Some(message) = target_record.entry().to_app_option::<ChatMessage>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("GameToChatMessages target is not a ChatMessage entry".into()));
+    };
+    // Author Check: Only the sender links their message
+    if create_link.author != message.sender {
+        return Ok(ValidateCallbackResult::Invalid("Author of GameToChatMessages link must be the message sender".into()));
+    }
+    // Note: Participation is checked by the coordinator; it depends on the latest game state
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,14 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/direct_message.rs
+use hdk::prelude::*;
+
+// A direct message that could not be delivered live, kept in the recipient's inbox.
+// The entry is public (linked from the recipient's AgentPubKey), so the inbox is for
+// casual messages only: anyone reading the DHT can see its content.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DirectMessage {
+    pub sender: AgentPubKey, // Must be the author
+    pub recipient: AgentPubKey,
+    pub content: String,
+    pub timestamp: Timestamp,
+}
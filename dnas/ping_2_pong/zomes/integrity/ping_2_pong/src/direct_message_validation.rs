@@ -0,0 +1,90 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/direct_message_validation.rs
+use hdk::prelude::*;
+use crate::direct_message::DirectMessage;
+use crate::chat_message_validation::MAX_CHAT_MESSAGE_LENGTH;
+
+// Validate creation of a DirectMessage entry.
+pub fn validate_create_direct_message(
+    action: &SignedActionHashed,
+    message: DirectMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Check Author
+    if message.sender != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Direct message sender must be the author".to_string()));
+    }
+    if message.recipient == message.sender {
+        return Ok(ValidateCallbackResult::Invalid("Cannot send a direct message to yourself".to_string()));
+    }
+
+    // 2. Check Content (same limits as chat)
+    if message.content.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Direct message cannot be empty".to_string()));
+    }
+    if message.content.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!("Direct message is too long (max {} characters)", MAX_CHAT_MESSAGE_LENGTH)));
+    }
+
+    // 3. Check Timestamp plausibility (within 5 minutes of the action)
+    if (message.timestamp.as_millis() - action.action().timestamp().as_millis()).abs() > 300_000 {
+        return Ok(ValidateCallbackResult::Invalid("Direct message timestamp is too far from the action timestamp".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Direct messages cannot be edited.
+pub fn validate_update_direct_message(
+    _action: &SignedActionHashed,
+    _updated_message: DirectMessage,
+    _original_message: &DirectMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Direct messages cannot be updated".to_string()))
+}
+
+// Only the sender can delete the entry; recipients clear their inbox by deleting the link.
+pub fn validate_delete_direct_message(
+    action: &SignedActionHashed,
+    original_message: DirectMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() != original_message.sender {
+        return Ok(ValidateCallbackResult::Invalid("Only the sender can delete a direct message".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a RecipientToDirectMessages link: from the recipient's key to a message
+// the link author sent them.
+pub fn validate_create_recipient_to_direct_messages_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an AgentPubKey
+    let Some(recipient) = create_link.base_address.clone().into_agent_pub_key() else {
+        return Ok(ValidateCallbackResult::Invalid("RecipientToDirectMessages base must be an AgentPubKey".into()));
+    };
+    // Target Check: Must be the ActionHash of a DirectMessage
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("RecipientToDirectMessages target must be a DirectMessage ActionHash".into()));
+    };
+    let target_record = must_get_valid_record(target_hash)?;
+    let Some(message) = target_record.entry().to_app_option::<DirectMessage>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("RecipientToDirectMessages target is not a DirectMessage entry".into()));
+    };
+    if message.recipient != recipient {
+        return Ok(ValidateCallbackResult::Invalid("RecipientToDirectMessages base must be the message recipient".into()));
+    }
+    // Author Check: Only the sender links their message
+    if create_link.author != message.sender {
+        return Ok(ValidateCallbackResult::Invalid("Author of RecipientToDirectMessages link must be the message sender".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate deleting a RecipientToDirectMessages link: the sender may retract it and
+// the recipient removes it once the message has been read.
+pub fn validate_delete_recipient_to_direct_messages_link(
+    action: &SignedActionHashed,
+    create_link: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    let author = action.action().author();
+    if *author == create_link.author || create_link.base_address.clone().into_agent_pub_key().as_ref() == Some(author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    Ok(ValidateCallbackResult::Invalid("Only the sender or recipient can remove a direct message from the inbox".into()))
+}
@@ -1,5 +1,6 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/game.rs
 use hdk::prelude::*;
+use crate::match_result::CoSignedMatchResult;
 
 // Define the Game Status enum.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -8,7 +9,15 @@ pub enum GameStatus {
     InProgress, // Game actively being played
     Finished,   // Game concluded, score recorded/recordable
     Abandoned,  // Game terminated by a player exiting
-    // Canceled? // Optional status
+    Canceled,   // Lobby closed before anyone joined
+}
+
+// Who may take the Player 2 seat of a Waiting game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub enum GameVisibility {
+    #[default]
+    Public,     // Anyone may join while the seat is empty
+    InviteOnly, // Only the player_2 set at creation (the invited player) may join
 }
 
 // Define the Game entry structure.
@@ -28,4 +37,45 @@ pub struct Game {
     pub ball_y: u32,
     // pub initial_ball_vector_x: i32, // Maybe store initial vector? Optional.
     // pub initial_ball_vector_y: i32,
-}
\ No newline at end of file
+    // Result signed by both players, set only on the transition to Finished.
+    pub result: Option<CoSignedMatchResult>,
+    // Games written before visibility existed are Public.
+    #[serde(default)]
+    pub visibility: GameVisibility,
+    // Original Tournament create action if this game is a bracket match (see tournament.rs).
+    #[serde(default)]
+    pub tournament_id: Option<ActionHash>,
+    // Original MatchSeries create action if this game is part of a series (see match_series.rs).
+    #[serde(default)]
+    pub series_id: Option<ActionHash>,
+}
+
+// Anchor path of the status index for a status, if games in that status are indexed.
+// Only the statuses a lobby needs to browse are indexed.
+pub fn status_index_path(status: &GameStatus) -> Option<&'static str> {
+    match status {
+        GameStatus::Waiting => Some("games.waiting"),
+        GameStatus::InProgress => Some("games.in_progress"),
+        _ => None,
+    }
+}
+
+// Tag of a GameStatusIndex link. The link targets the Game action that entered
+// `status`; `game_id` is the original Game create action identifying the game.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct GameStatusIndexTag {
+    pub status: GameStatus,
+    pub game_id: ActionHash,
+}
+
+impl GameStatusIndexTag {
+    pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(self.clone()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+
+    pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+        GameStatusIndexTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.clone().into_inner())))
+            .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+    }
+}
@@ -1,6 +1,9 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/game_validation.rs
 use hdk::prelude::*;
-use crate::game::{Game, GameStatus};
+use crate::game::{Game, GameStatus, GameVisibility, GameStatusIndexTag, status_index_path};
+use crate::match_result_validation::{validate_co_signed_result, validate_result_references_game};
+use crate::tournament_validation::validate_tournament_game_author;
+use crate::match_series_validation::validate_series_game;
 // Use core::time::Duration for stability if hdk::prelude::Duration is problematic
 use core::time::Duration;
 // Import Add/Sub traits for Timestamp arithmetic
@@ -13,12 +16,28 @@ pub fn validate_create_game(
 ) -> ExternResult<ValidateCallbackResult> {
     // 1. Check Author: Ensure the creator is Player 1 or Player 2 (if specified).
     let author = action.action().author();
+    if game.tournament_id.is_some() {
+        // Bracket matches are also created by the tournament organiser
+        let author_check = validate_tournament_game_author(author, &game)?;
+        if author_check != ValidateCallbackResult::Valid {
+            return Ok(author_check);
+        }
+        if game.visibility != GameVisibility::InviteOnly {
+            return Ok(ValidateCallbackResult::Invalid("Tournament games must be invite-only".to_string()));
+        }
     // Allow Player 2 to create only if they are specified in the entry
-    if game.player_1 != *author && game.player_2.as_ref() != Some(author) {
+    } else if game.player_1 != *author && game.player_2.as_ref() != Some(author) {
          return Ok(ValidateCallbackResult::Invalid(
              "Game creator must be Player 1 or Player 2 specified in the entry".to_string(),
          ));
     }
+    // Series games are invite-only games between the series' players
+    if game.series_id.is_some() {
+        let series_check = validate_series_game(&game)?;
+        if series_check != ValidateCallbackResult::Valid {
+            return Ok(series_check);
+        }
+    }
 
     // 2. Check Initial Status: Must be 'Waiting'.
     if game.game_status != GameStatus::Waiting {
@@ -36,7 +55,21 @@ pub fn validate_create_game(
          }
      }
 
-     // 4. Check Timestamp plausibility (within reason, e.g., +/- 5 mins from action time)
+     // 4. Check Visibility: an invite-only game reserves its seat for the invited player.
+     if game.visibility == GameVisibility::InviteOnly && game.player_2.is_none() {
+         return Ok(ValidateCallbackResult::Invalid(
+             "Invite-only game must be created with the invited Player 2".to_string(),
+         ));
+     }
+
+     // 5. Check Result: a new game cannot carry a final result.
+     if game.result.is_some() {
+         return Ok(ValidateCallbackResult::Invalid(
+             "Game cannot be created with a match result".to_string(),
+         ));
+     }
+
+     // 6. Check Timestamp plausibility (within reason, e.g., +/- 5 mins from action time)
      let action_time = action.action().timestamp();
      let five_minutes = Duration::from_secs(300);
 
@@ -59,6 +92,15 @@ pub fn validate_create_game(
 }
 
 // Validate updating a Game entry.
+//
+// The game lifecycle is an explicit state machine:
+//
+//   Waiting    -> InProgress  (Player 2 joins; author must be the joining Player 2)
+//   Waiting    -> Canceled    (lobby closed before anyone joined; author must be a listed participant)
+//   InProgress -> Finished    (game played to completion; author must be a participant)
+//   InProgress -> Abandoned   (a participant left mid-game; author must be a participant)
+//
+// Finished, Abandoned and Canceled are terminal: no further updates are accepted.
 pub fn validate_update_game(
     action: &SignedActionHashed,
     updated_game: Game,
@@ -66,70 +108,92 @@ pub fn validate_update_game(
 ) -> ExternResult<ValidateCallbackResult> {
 
     let author = action.action().author();
-
-    // --- Author Check ---
-    // Allow update if:
-    // 1. Author is Player 1
-    // 2. Author is the (existing) Player 2
-    // 3. Game is Waiting->InProgress AND Author is the NEW Player 2 being added
-    let is_player1 = original_game.player_1 == *author;
-    let is_existing_player2 = original_game.player_2.as_ref() == Some(author);
-    let is_new_player2_joining = original_game.game_status == GameStatus::Waiting
-                                 && updated_game.game_status == GameStatus::InProgress
-                                 && updated_game.player_2.as_ref() == Some(author);
-
-    if !is_player1 && !is_existing_player2 && !is_new_player2_joining {
-         return Ok(ValidateCallbackResult::Invalid(
-             "Update author must be Player 1, existing Player 2, or new Player 2 joining a Waiting game".to_string(),
-         ));
-    }
+    let is_participant = original_game.player_1 == *author || original_game.player_2.as_ref() == Some(author);
 
     // --- Immutability Check ---
     if updated_game.player_1 != original_game.player_1
         || updated_game.created_at != original_game.created_at
-        // Allow player_2 to change ONLY when going from Waiting -> InProgress
-        || (updated_game.player_2 != original_game.player_2 && !(original_game.game_status == GameStatus::Waiting && updated_game.game_status == GameStatus::InProgress))
+        || updated_game.visibility != original_game.visibility
+        || updated_game.tournament_id != original_game.tournament_id
+        || updated_game.series_id != original_game.series_id
     {
         return Ok(ValidateCallbackResult::Invalid(
-            "Cannot change player_1, created_at, or player_2 (except when joining)".to_string(),
+            "Cannot change player_1, created_at, visibility, tournament or series of a Game".to_string(),
         ));
     }
-    // Ensure if player_2 changed, it went from None to Some
-     if updated_game.player_2 != original_game.player_2 {
-          if original_game.player_2.is_some() || updated_game.player_2.is_none() {
-                return Ok(ValidateCallbackResult::Invalid("Player 2 can only be changed from None to Some when joining".into()));
-          }
-          // Also check the author was the new player 2 (already covered by author check above)
-     }
-
+    // Player 2 may only change from None to Some, and only when joining
+    let is_join = original_game.game_status == GameStatus::Waiting && updated_game.game_status == GameStatus::InProgress;
+    if updated_game.player_2 != original_game.player_2
+        && (!is_join || original_game.player_2.is_some() || updated_game.player_2.is_none())
+    {
+        return Ok(ValidateCallbackResult::Invalid("Player 2 can only be changed from None to Some when joining".into()));
+    }
 
      // --- Prevent Real-time State Updates via DHT ---
-     if updated_game.player_1_paddle != original_game.player_1_paddle
+     // Paddle/ball positions may only be snapshotted when the game finishes.
+     if (updated_game.player_1_paddle != original_game.player_1_paddle
          || updated_game.player_2_paddle != original_game.player_2_paddle
          || updated_game.ball_x != original_game.ball_x
-         || updated_game.ball_y != original_game.ball_y
+         || updated_game.ball_y != original_game.ball_y)
+         && updated_game.game_status != GameStatus::Finished
      {
-          if updated_game.game_status == GameStatus::Finished && original_game.game_status != GameStatus::Finished {
-               warn!("Allowing update to paddle/ball positions as game transitions to Finished state.");
-          } else if updated_game.game_status == GameStatus::Finished && original_game.game_status == GameStatus::Finished {
-                return Ok(ValidateCallbackResult::Invalid( "Cannot update paddle/ball positions on an already Finished game".to_string() ));
-          } else {
-                return Ok(ValidateCallbackResult::Invalid( "Cannot update paddle/ball positions via DHT entry update (use signals)".to_string() ));
-          }
+          return Ok(ValidateCallbackResult::Invalid( "Cannot update paddle/ball positions via DHT entry update (use signals)".to_string() ));
      }
 
+    // --- Result Check ---
+    // Only the transition to Finished may set the result (checked below).
+    if updated_game.result != original_game.result && updated_game.game_status != GameStatus::Finished {
+        return Ok(ValidateCallbackResult::Invalid("Match result can only be set when finishing a game".into()));
+    }
+
     // --- Status Transitions Check ---
     match (&original_game.game_status, &updated_game.game_status) {
         (GameStatus::Waiting, GameStatus::InProgress) => {
-             if updated_game.player_2.is_none() { return Ok(ValidateCallbackResult::Invalid("Cannot transition to InProgress without Player 2 being set".into())); }
-             // Check author IS the new player 2 (already covered by refined author check)
-             // if updated_game.player_2.as_ref() != Some(author) { return Ok(ValidateCallbackResult::Invalid("Join must be performed by Player 2".into())); }
+             // The author must be the Player 2 now set on the game (either newly joining or the invited player)
+             if updated_game.player_2.as_ref() != Some(author) {
+                 return Ok(ValidateCallbackResult::Invalid("Join must be performed by the joining Player 2".into()));
+             }
+             // Invite-only games can only be joined by the Player 2 reserved at creation
+             if original_game.visibility == GameVisibility::InviteOnly && original_game.player_2.as_ref() != Some(author) {
+                 return Ok(ValidateCallbackResult::Invalid("Only the invited player can join an invite-only game".into()));
+             }
+        },
+        (GameStatus::Waiting, GameStatus::Canceled) => {
+             if !is_participant {
+                 return Ok(ValidateCallbackResult::Invalid("Only game participants can cancel a Waiting game".into()));
+             }
+        },
+        (GameStatus::InProgress, GameStatus::Finished) => {
+             if !is_participant {
+                 return Ok(ValidateCallbackResult::Invalid("Only game participants can finish a game".into()));
+             }
+             // The final state must carry a result co-signed by both players of this game.
+             let Some(co_signed) = &updated_game.result else {
+                 return Ok(ValidateCallbackResult::Invalid("Finished game must carry a co-signed match result".into()));
+             };
+             if co_signed.result.player_1 != updated_game.player_1 || Some(&co_signed.result.player_2) != updated_game.player_2.as_ref() {
+                 return Ok(ValidateCallbackResult::Invalid("Match result players do not match the game players".into()));
+             }
+             // The Finished update must follow the join the result refers to
+             if let Action::Update(update) = action.action() {
+                 if update.original_action_address != co_signed.result.join_action {
+                     return Ok(ValidateCallbackResult::Invalid("Finished game must update the join the match result refers to".into()));
+                 }
+             }
+             let signatures_check = validate_co_signed_result(co_signed)?;
+             if signatures_check != ValidateCallbackResult::Valid {
+                 return Ok(signatures_check);
+             }
+             return validate_result_references_game(co_signed);
+        },
+        (GameStatus::InProgress, GameStatus::Abandoned) => {
+             if !is_participant {
+                 return Ok(ValidateCallbackResult::Invalid("Only game participants can abandon a game".into()));
+             }
+        },
+        (GameStatus::Finished, _) | (GameStatus::Abandoned, _) | (GameStatus::Canceled, _) => {
+             return Ok(ValidateCallbackResult::Invalid(format!( "Game in terminal status {:?} cannot be updated", original_game.game_status )));
         },
-        (GameStatus::InProgress, GameStatus::Finished) => { /* Allow */ },
-        (GameStatus::Finished, GameStatus::Finished) => { /* Allow */ },
-        // Disallow other transitions explicitly for clarity
-        (GameStatus::Waiting, GameStatus::Waiting) => return Ok(ValidateCallbackResult::Invalid("No valid updates allowed for 'Waiting' game status".into())),
-        (GameStatus::InProgress, GameStatus::InProgress) => return Ok(ValidateCallbackResult::Invalid("No valid updates allowed for 'InProgress' game status".into())),
         (from, to) => { return Ok(ValidateCallbackResult::Invalid(format!( "Invalid game status transition from {:?} to {:?}", from, to ))); }
     }
 
@@ -162,5 +226,66 @@ pub fn validate_delete_game(
 }
 
 
-// FIX: Remove helper function that uses `get`
-// fn must_get_valid_record(action_hash: ActionHash) -> ExternResult<Record> { ... }
\ No newline at end of file
+// Validate a GameStatusIndex link: the base must be the index anchor of the tagged
+// status, and the target must be a version of the tagged game that is in that status.
+pub fn validate_game_status_index_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    let Ok(tag) = GameStatusIndexTag::from_link_tag(&create_link.tag) else {
+        return Ok(ValidateCallbackResult::Invalid("GameStatusIndex tag must be a GameStatusIndexTag".into()));
+    };
+    let Some(index_path) = status_index_path(&tag.status) else {
+        return Ok(ValidateCallbackResult::Invalid(format!("Games with status {:?} are not indexed", tag.status)));
+    };
+    if create_link.base_address != AnyLinkableHash::from(Path::from(index_path).path_entry_hash()?) {
+        return Ok(ValidateCallbackResult::Invalid(format!("GameStatusIndex base must be the '{}' anchor", index_path)));
+    }
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameStatusIndex target must be a Game ActionHash".into()));
+    };
+
+    // Target must be the game itself or an update made directly on the original create
+    // (both indexed statuses are at most one transition away from creation).
+    let target_record = must_get_valid_record(target_hash.clone())?;
+    let belongs_to_game = match target_record.action() {
+        Action::Create(_) => target_hash == tag.game_id,
+        Action::Update(update) => update.original_action_address == tag.game_id,
+        _ => false,
+    };
+    if !belongs_to_game {
+        return Ok(ValidateCallbackResult::Invalid("GameStatusIndex target is not a version of the tagged game".into()));
+    }
+    let Some(game) = target_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("GameStatusIndex target is not a Game entry".into()));
+    };
+    if game.game_status != tag.status {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "GameStatusIndex target game is {:?}, not {:?}", game.game_status, tag.status
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a GameToSpectators link: from a Game to an agent watching it. Agents only
+// register themselves, and player 1 cannot watch their own game.
+pub fn validate_game_to_spectators_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be a Game ActionHash
+    let Some(game_hash) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToSpectators base must be a Game ActionHash".into()));
+    };
+    let game_record = must_get_valid_record(game_hash)?;
+    let Some(game) = game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("GameToSpectators base is not a Game entry".into()));
+    };
+    // Target Check: Must be an AgentPubKey
+    let Some(spectator) = create_link.target_address.clone().into_agent_pub_key() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToSpectators target must be an AgentPubKey".into()));
+    };
+    // Author Check: Agents can only register themselves
+    if create_link.author != spectator {
+        return Ok(ValidateCallbackResult::Invalid("Agents can only register themselves as spectators".into()));
+    }
+    if game.player_1 == spectator || game.player_2.as_ref() == Some(&spectator) {
+        return Ok(ValidateCallbackResult::Invalid("Players cannot spectate their own game".into()));
+    }
+    // Note: Player 2 of a public game is only known from the latest state, which the coordinator checks
+    Ok(ValidateCallbackResult::Valid)
+}
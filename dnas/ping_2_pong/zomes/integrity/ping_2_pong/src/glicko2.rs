@@ -0,0 +1,190 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/glicko2.rs
+// Glicko-2 rating math (Glickman, "Example of the Glicko-2 system", 2013).
+//
+// Ratings are kept in the Glicko scale (1500 / 350 / 0.06 for a new player) and
+// converted to the internal Glicko-2 scale only while updating.
+// This module is pure (no host calls): the coordinator uses it to compute rating
+// updates and validation re-runs it to check them, and it can be unit tested.
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_RATING: f64 = 1500.0;
+pub const DEFAULT_DEVIATION: f64 = 350.0;
+pub const DEFAULT_VOLATILITY: f64 = 0.06;
+/// System constant constraining volatility changes (Glickman suggests 0.3 to 1.2).
+pub const TAU: f64 = 0.5;
+
+const SCALE: f64 = 173.7178; // Glicko <-> Glicko-2 conversion factor
+const CONVERGENCE: f64 = 0.000_001;
+
+/// A player's rating in the Glicko scale.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Glicko2Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for Glicko2Rating {
+    fn default() -> Self {
+        Glicko2Rating { rating: DEFAULT_RATING, deviation: DEFAULT_DEVIATION, volatility: DEFAULT_VOLATILITY }
+    }
+}
+
+/// One game of a rating period from the player's point of view.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GameOutcome {
+    pub opponent: Glicko2Rating,
+    pub score: f64, // 1.0 win, 0.5 draw, 0.0 loss
+}
+
+impl GameOutcome {
+    pub const WIN: f64 = 1.0;
+    pub const DRAW: f64 = 0.5;
+    pub const LOSS: f64 = 0.0;
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (std::f64::consts::PI * std::f64::consts::PI)).sqrt()
+}
+
+fn expected(mu: f64, mu_j: f64, phi_j: f64) -> f64 {
+    1.0 / (1.0 + (-g(phi_j) * (mu - mu_j)).exp())
+}
+
+// Step 5: new volatility via the Illinois algorithm.
+fn new_volatility(sigma: f64, phi: f64, v: f64, delta: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta * delta - phi * phi - v - ex) / (2.0 * (phi * phi + v + ex).powi(2)) - (x - a) / (TAU * TAU)
+    };
+
+    let mut big_a = a;
+    let mut big_b = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+    let mut f_a = f(big_a);
+    let mut f_b = f(big_b);
+    while (big_b - big_a).abs() > CONVERGENCE {
+        let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+        let f_c = f(big_c);
+        if f_c * f_b <= 0.0 {
+            big_a = big_b;
+            f_a = f_b;
+        } else {
+            f_a /= 2.0;
+        }
+        big_b = big_c;
+        f_b = f_c;
+    }
+    (big_a / 2.0).exp()
+}
+
+/// Rates a player over one rating period. With no games, only the deviation grows.
+pub fn rate(player: Glicko2Rating, games: &[GameOutcome]) -> Glicko2Rating {
+    let mu = (player.rating - DEFAULT_RATING) / SCALE;
+    let phi = player.deviation / SCALE;
+
+    if games.is_empty() {
+        let phi_star = (phi * phi + player.volatility * player.volatility).sqrt();
+        return Glicko2Rating { deviation: (phi_star * SCALE).min(DEFAULT_DEVIATION), ..player };
+    }
+
+    // Steps 3 and 4: estimated variance and improvement
+    let mut v_inv = 0.0;
+    let mut improvement = 0.0;
+    for game in games {
+        let mu_j = (game.opponent.rating - DEFAULT_RATING) / SCALE;
+        let phi_j = game.opponent.deviation / SCALE;
+        let e = expected(mu, mu_j, phi_j);
+        v_inv += g(phi_j).powi(2) * e * (1.0 - e);
+        improvement += g(phi_j) * (game.score - e);
+    }
+    let v = 1.0 / v_inv;
+    let delta = v * improvement;
+
+    // Steps 5 to 7
+    let sigma = new_volatility(player.volatility, phi, v, delta);
+    let phi_star = (phi * phi + sigma * sigma).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+    let new_mu = mu + new_phi * new_phi * improvement;
+
+    // Step 8: back to the Glicko scale
+    Glicko2Rating {
+        rating: new_mu * SCALE + DEFAULT_RATING,
+        deviation: (new_phi * SCALE).min(DEFAULT_DEVIATION),
+        volatility: sigma,
+    }
+}
+
+/// Rates a single game, the usual case after a match finishes.
+pub fn rate_game(player: Glicko2Rating, opponent: Glicko2Rating, score: f64) -> Glicko2Rating {
+    rate(player, &[GameOutcome { opponent, score }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(rating: f64, deviation: f64) -> Glicko2Rating {
+        Glicko2Rating { rating, deviation, volatility: DEFAULT_VOLATILITY }
+    }
+
+    #[test]
+    fn matches_glickman_example() {
+        let player = rating(1500.0, 200.0);
+        let games = [
+            GameOutcome { opponent: rating(1400.0, 30.0), score: GameOutcome::WIN },
+            GameOutcome { opponent: rating(1550.0, 100.0), score: GameOutcome::LOSS },
+            GameOutcome { opponent: rating(1700.0, 300.0), score: GameOutcome::LOSS },
+        ];
+        let updated = rate(player, &games);
+        assert!((updated.rating - 1464.06).abs() < 0.01, "rating {}", updated.rating);
+        assert!((updated.deviation - 151.52).abs() < 0.01, "deviation {}", updated.deviation);
+        assert!((updated.volatility - 0.05999).abs() < 0.00001, "volatility {}", updated.volatility);
+    }
+
+    #[test]
+    fn winner_gains_and_loser_drops() {
+        let a = Glicko2Rating::default();
+        let b = Glicko2Rating::default();
+        let a_after = rate_game(a, b, GameOutcome::WIN);
+        let b_after = rate_game(b, a, GameOutcome::LOSS);
+        assert!(a_after.rating > a.rating);
+        assert!(b_after.rating < b.rating);
+        assert!((a_after.rating - DEFAULT_RATING - (DEFAULT_RATING - b_after.rating)).abs() < 1e-9);
+        assert!(a_after.deviation < a.deviation);
+    }
+
+    #[test]
+    fn draw_between_equals_keeps_rating() {
+        let a = Glicko2Rating::default();
+        let after = rate_game(a, a, GameOutcome::DRAW);
+        assert!((after.rating - a.rating).abs() < 1e-9);
+        assert!(after.deviation < a.deviation);
+    }
+
+    #[test]
+    fn upset_moves_rating_more_than_expected_win() {
+        let player = rating(1500.0, 100.0);
+        let upset = rate_game(player, rating(1800.0, 100.0), GameOutcome::WIN);
+        let expected_win = rate_game(player, rating(1200.0, 100.0), GameOutcome::WIN);
+        assert!(upset.rating - player.rating > expected_win.rating - player.rating);
+    }
+
+    #[test]
+    fn idle_period_only_grows_deviation() {
+        let player = rating(1600.0, 50.0);
+        let after = rate(player, &[]);
+        assert_eq!(after.rating, player.rating);
+        assert_eq!(after.volatility, player.volatility);
+        assert!(after.deviation > player.deviation);
+        assert!(rate(Glicko2Rating::default(), &[]).deviation <= DEFAULT_DEVIATION);
+    }
+}
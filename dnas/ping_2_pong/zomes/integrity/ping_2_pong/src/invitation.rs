@@ -0,0 +1,35 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/invitation.rs
+use hdk::prelude::*;
+
+// Longest lifetime an inviter may give an invitation.
+pub const MAX_INVITATION_TTL_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum InvitationStatus {
+    Pending,   // Waiting for the invitee (until expires_at)
+    Accepted,  // Invitee joined the game
+    Declined,  // Invitee turned it down
+    Cancelled, // Inviter withdrew it
+}
+
+// Invitation to join a Waiting game, linked from the invitee's AgentPubKey so it
+// survives the invitee being offline when it was sent. The status is changed by
+// updating the original entry; expiry is time-based and never written.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Invitation {
+    pub game_id: ActionHash, // Original Game create action; the inviter is player 1
+    pub inviter: AgentPubKey, // Must be the author
+    pub invitee: AgentPubKey,
+    pub message: String,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub status: InvitationStatus,
+}
+
+impl Invitation {
+    // Pending and not yet expired at `now`.
+    pub fn is_open_at(&self, now: Timestamp) -> bool {
+        self.status == InvitationStatus::Pending && now < self.expires_at
+    }
+}
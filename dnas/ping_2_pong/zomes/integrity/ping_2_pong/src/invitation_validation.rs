@@ -0,0 +1,135 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/invitation_validation.rs
+use hdk::prelude::*;
+use crate::invitation::{Invitation, InvitationStatus, MAX_INVITATION_TTL_MS};
+use crate::chat_message_validation::MAX_CHAT_MESSAGE_LENGTH;
+use crate::game::Game;
+
+// Validate creation of an Invitation entry.
+pub fn validate_create_invitation(
+    action: &SignedActionHashed,
+    invitation: Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Check Author and parties
+    if invitation.inviter != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Invitation inviter must be the author".to_string()));
+    }
+    if invitation.invitee == invitation.inviter {
+        return Ok(ValidateCallbackResult::Invalid("Cannot invite yourself".to_string()));
+    }
+    if invitation.status != InvitationStatus::Pending {
+        return Ok(ValidateCallbackResult::Invalid("New invitations must be Pending".to_string()));
+    }
+    if invitation.message.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!("Invitation message is too long (max {} characters)", MAX_CHAT_MESSAGE_LENGTH)));
+    }
+
+    // 2. Check Timestamps: created close to the action, expiring within the allowed lifetime
+    let created_ms = invitation.created_at.as_millis();
+    if (created_ms - action.action().timestamp().as_millis()).abs() > 300_000 {
+        return Ok(ValidateCallbackResult::Invalid("Invitation created_at is too far from the action timestamp".to_string()));
+    }
+    let ttl_ms = invitation.expires_at.as_millis() - created_ms;
+    if ttl_ms <= 0 || ttl_ms > MAX_INVITATION_TTL_MS {
+        return Ok(ValidateCallbackResult::Invalid(format!("Invitation must expire within {} ms of its creation", MAX_INVITATION_TTL_MS)));
+    }
+
+    // 3. Check Game: the inviter owns it and the seat is free for the invitee
+    let game_record = must_get_valid_record(invitation.game_id.clone())?;
+    let Some(game) = game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("Invitation game_id does not point to a Game entry".to_string()));
+    };
+    if game.player_1 != invitation.inviter {
+        return Ok(ValidateCallbackResult::Invalid("Only player 1 of a game can invite to it".to_string()));
+    }
+    if game.player_2.as_ref().is_some_and(|p2| *p2 != invitation.invitee) {
+        return Ok(ValidateCallbackResult::Invalid("Game is reserved for a different player 2".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate updating an Invitation: a single status change from Pending, made before it
+// expires, by the invitee (accept/decline) or the inviter (cancel).
+pub fn validate_update_invitation(
+    action: &SignedActionHashed,
+    updated_invitation: Invitation,
+    original_invitation: &Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Only the status can change
+    let unchanged = Invitation { status: updated_invitation.status.clone(), ..original_invitation.clone() };
+    if updated_invitation != unchanged {
+        return Ok(ValidateCallbackResult::Invalid("Only the status of an invitation can be updated".to_string()));
+    }
+
+    // 2. Check Transition (the original must still be open). Updates are checked against the
+    //    original only, so an accept racing a decline or cancel can both be valid; readers
+    //    resolve which one stands.
+    if original_invitation.status != InvitationStatus::Pending {
+        return Ok(ValidateCallbackResult::Invalid("Invitation has already been answered".to_string()));
+    }
+    if action.action().timestamp() >= original_invitation.expires_at {
+        return Ok(ValidateCallbackResult::Invalid("Invitation has expired".to_string()));
+    }
+
+    // 3. Check Author for the new status
+    let author = action.action().author();
+    let allowed = match updated_invitation.status {
+        InvitationStatus::Pending => return Ok(ValidateCallbackResult::Invalid("Invitation update must change its status".to_string())),
+        InvitationStatus::Accepted | InvitationStatus::Declined => *author == original_invitation.invitee,
+        InvitationStatus::Cancelled => *author == original_invitation.inviter,
+    };
+    if !allowed {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Agent is not allowed to mark this invitation {:?}", updated_invitation.status
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Only the inviter can delete the entry; the invitee clears their inbox by deleting the link.
+pub fn validate_delete_invitation(
+    action: &SignedActionHashed,
+    original_invitation: Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() != original_invitation.inviter {
+        return Ok(ValidateCallbackResult::Invalid("Only the inviter can delete an invitation".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate an InviteeToInvitations link: from the invitee's key to an invitation
+// the link author sent them.
+pub fn validate_create_invitee_to_invitations_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an AgentPubKey
+    let Some(invitee) = create_link.base_address.clone().into_agent_pub_key() else {
+        return Ok(ValidateCallbackResult::Invalid("InviteeToInvitations base must be an AgentPubKey".into()));
+    };
+    // Target Check: Must be the ActionHash of an Invitation
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("InviteeToInvitations target must be an Invitation ActionHash".into()));
+    };
+    let target_record = must_get_valid_record(target_hash)?;
+    let Some(invitation) = target_record.entry().to_app_option::<Invitation>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("InviteeToInvitations target is not an Invitation entry".into()));
+    };
+    if invitation.invitee != invitee {
+        return Ok(ValidateCallbackResult::Invalid("InviteeToInvitations base must be the invitee".into()));
+    }
+    // Author Check: Only the inviter links their invitation
+    if create_link.author != invitation.inviter {
+        return Ok(ValidateCallbackResult::Invalid("Author of InviteeToInvitations link must be the inviter".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate deleting an InviteeToInvitations link: the inviter removes it on cancel and
+// the invitee once the invitation is answered or expired.
+pub fn validate_delete_invitee_to_invitations_link(
+    action: &SignedActionHashed,
+    create_link: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    let author = action.action().author();
+    if *author == create_link.author || create_link.base_address.clone().into_agent_pub_key().as_ref() == Some(author) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    Ok(ValidateCallbackResult::Invalid("Only the inviter or invitee can remove an invitation link".into()))
+}
@@ -0,0 +1,149 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/league.rs
+use hdk::prelude::*;
+
+pub const MIN_LEAGUE_MEMBERS: usize = 2;
+pub const MAX_LEAGUE_MEMBERS: usize = 20; // 190 fixtures in a single round robin
+pub const MAX_LEAGUE_NAME_LENGTH: usize = 64;
+
+// A season: a window of time that leagues run in and leaderboards can be scoped to.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Season {
+    pub name: String,
+    pub created_by: AgentPubKey, // Also the author
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,      // Exclusive
+    pub created_at: Timestamp,
+}
+
+impl Season {
+    pub fn contains(&self, time: Timestamp) -> bool {
+        self.starts_at <= time && time < self.ends_at
+    }
+}
+
+// A round-robin league within a season. Members are fixed at creation, so the
+// schedule (`round_robin_schedule`) never changes; fixtures are ordinary Games
+// linked from the league with LeagueToGames (see coordinator league.rs).
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct League {
+    pub name: String,
+    pub organiser: AgentPubKey, // Also the author; need not be a member
+    pub season_id: ActionHash,  // Season create action; fixtures count only when created within it
+    pub members: Vec<AgentPubKey>,
+    pub created_at: Timestamp,
+}
+
+// Tag of a LeagueToGames link: the fixture the linked game was played for.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct LeagueFixtureTag {
+    pub round: u32,
+    pub index: u32, // Position of the fixture within its round
+}
+
+impl LeagueFixtureTag {
+    pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(self.clone()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+
+    pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+        LeagueFixtureTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.clone().into_inner())))
+            .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+    }
+}
+
+// Single round robin by the circle method: everyone meets everyone once. Each round
+// is a list of (player 1, player 2) fixtures; with an odd number of members one sits
+// out each round. Sides alternate between rounds so nobody is always player 1.
+// Lives in the integrity zome so LeagueToGames links can be checked against it.
+pub fn round_robin_schedule(members: &[AgentPubKey]) -> Vec<Vec<(AgentPubKey, AgentPubKey)>> {
+    let mut circle: Vec<Option<&AgentPubKey>> = members.iter().map(Some).collect();
+    if circle.len() % 2 == 1 {
+        circle.push(None); // Bye
+    }
+    let n = circle.len();
+    let mut rounds = Vec::new();
+    for round in 0..n.saturating_sub(1) {
+        let mut fixtures = Vec::new();
+        for i in 0..n / 2 {
+            if let (Some(a), Some(b)) = (circle[i], circle[n - 1 - i]) {
+                let (home, away) = if round % 2 == 0 { (a, b) } else { (b, a) };
+                fixtures.push((home.clone(), away.clone()));
+            }
+        }
+        rounds.push(fixtures);
+        // Keep the first position fixed and rotate the rest by one
+        let last = circle.remove(n - 1);
+        circle.insert(1, last);
+    }
+    rounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(count: u8) -> Vec<AgentPubKey> {
+        (0..count).map(|n| AgentPubKey::from_raw_36(vec![n; 36])).collect()
+    }
+
+    #[test]
+    fn every_pair_meets_exactly_once() {
+        for count in [2, 3, 4, 5, 8, 9, MAX_LEAGUE_MEMBERS as u8] {
+            let members = members(count);
+            let fixtures: Vec<(AgentPubKey, AgentPubKey)> = round_robin_schedule(&members).into_iter().flatten().collect();
+            let n = members.len();
+            assert_eq!(fixtures.len(), n * (n - 1) / 2, "{} members", count);
+            for (i, a) in members.iter().enumerate() {
+                for b in &members[i + 1..] {
+                    let meetings = fixtures
+                        .iter()
+                        .filter(|(home, away)| (home == a && away == b) || (home == b && away == a))
+                        .count();
+                    assert_eq!(meetings, 1, "{} members", count);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nobody_plays_twice_in_a_round() {
+        for count in [2, 3, 4, 5, 8, 9, MAX_LEAGUE_MEMBERS as u8] {
+            for round in round_robin_schedule(&members(count)) {
+                let mut players: Vec<&AgentPubKey> = round.iter().flat_map(|(home, away)| [home, away]).collect();
+                let total = players.len();
+                players.sort();
+                players.dedup();
+                assert_eq!(players.len(), total, "{} members", count);
+            }
+        }
+    }
+
+    #[test]
+    fn rounds_and_byes_fit_the_member_count() {
+        // Even: n - 1 full rounds
+        let even = round_robin_schedule(&members(6));
+        assert_eq!(even.len(), 5);
+        assert!(even.iter().all(|round| round.len() == 3));
+        // Odd: n rounds, one member sitting out each, and each member sits out once
+        let members = members(5);
+        let odd = round_robin_schedule(&members);
+        assert_eq!(odd.len(), 5);
+        assert!(odd.iter().all(|round| round.len() == 2));
+        for member in &members {
+            let byes = odd
+                .iter()
+                .filter(|round| round.iter().all(|(home, away)| home != member && away != member))
+                .count();
+            assert_eq!(byes, 1);
+        }
+    }
+
+    #[test]
+    fn single_member_has_no_fixtures() {
+        assert!(round_robin_schedule(&members(1)).into_iter().flatten().next().is_none());
+        assert!(round_robin_schedule(&[]).is_empty());
+    }
+}
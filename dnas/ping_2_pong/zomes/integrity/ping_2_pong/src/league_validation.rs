@@ -0,0 +1,187 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/league_validation.rs
+use hdk::prelude::*;
+use crate::league::{
+    Season, League, LeagueFixtureTag, round_robin_schedule,
+    MIN_LEAGUE_MEMBERS, MAX_LEAGUE_MEMBERS, MAX_LEAGUE_NAME_LENGTH,
+};
+use crate::game::Game;
+
+fn check_name(name: &str) -> Option<ValidateCallbackResult> {
+    let length = name.trim().chars().count();
+    if length == 0 || length > MAX_LEAGUE_NAME_LENGTH {
+        return Some(ValidateCallbackResult::Invalid(format!("Name must be 1 to {} characters", MAX_LEAGUE_NAME_LENGTH)));
+    }
+    None
+}
+
+fn check_created_at(action: &SignedActionHashed, created_at: Timestamp) -> Option<ValidateCallbackResult> {
+    if (created_at.as_millis() - action.action().timestamp().as_millis()).abs() > 300_000 {
+        return Some(ValidateCallbackResult::Invalid("created_at is too far from the action timestamp".to_string()));
+    }
+    None
+}
+
+fn must_get_entry<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(hash: &ActionHash) -> ExternResult<Option<T>> {
+    must_get_valid_record(hash.clone())?
+        .entry()
+        .to_app_option::<T>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+}
+
+// Validate creation of a Season entry.
+pub fn validate_create_season(
+    action: &SignedActionHashed,
+    season: Season,
+) -> ExternResult<ValidateCallbackResult> {
+    if season.created_by != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Season created_by must be the author".to_string()));
+    }
+    if let Some(invalid) = check_name(&season.name).or(check_created_at(action, season.created_at)) {
+        return Ok(invalid);
+    }
+    if season.ends_at <= season.starts_at {
+        return Ok(ValidateCallbackResult::Invalid("Season must end after it starts".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Seasons are fixed once created: leagues and leaderboards rely on their boundaries.
+pub fn validate_update_season(
+    _action: &SignedActionHashed,
+    _season: Season,
+    _original_season: &Season,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Seasons cannot be updated".to_string()))
+}
+
+// Only the creator can delete a season, and only before it starts.
+pub fn validate_delete_season(
+    action: &SignedActionHashed,
+    original_season: Season,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() != original_season.created_by {
+        return Ok(ValidateCallbackResult::Invalid("Only the creator can delete a season".to_string()));
+    }
+    if action.action().timestamp() >= original_season.starts_at {
+        return Ok(ValidateCallbackResult::Invalid("A season cannot be deleted once it has started".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate creation of a League entry: distinct members, in a season that has not ended.
+pub fn validate_create_league(
+    action: &SignedActionHashed,
+    league: League,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Check Author
+    if league.organiser != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("League organiser must be the author".to_string()));
+    }
+    if let Some(invalid) = check_name(&league.name).or(check_created_at(action, league.created_at)) {
+        return Ok(invalid);
+    }
+
+    // 2. Check Members
+    if league.members.len() < MIN_LEAGUE_MEMBERS || league.members.len() > MAX_LEAGUE_MEMBERS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "League must have between {} and {} members", MIN_LEAGUE_MEMBERS, MAX_LEAGUE_MEMBERS
+        )));
+    }
+    if league.members.iter().enumerate().any(|(i, member)| league.members[..i].contains(member)) {
+        return Ok(ValidateCallbackResult::Invalid("A player can only be a league member once".to_string()));
+    }
+
+    // 3. Check Season
+    let Some(season) = must_get_entry::<Season>(&league.season_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("League season_id does not point to a Season entry".to_string()));
+    };
+    if action.action().timestamp() >= season.ends_at {
+        return Ok(ValidateCallbackResult::Invalid("Cannot create a league in a season that has ended".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Members (and so the schedule) are fixed at creation.
+pub fn validate_update_league(
+    _action: &SignedActionHashed,
+    _league: League,
+    _original_league: &League,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Leagues cannot be updated".to_string()))
+}
+
+pub fn validate_delete_league(
+    action: &SignedActionHashed,
+    original_league: League,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() != original_league.organiser {
+        return Ok(ValidateCallbackResult::Invalid("Only the organiser can delete a league".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a SeasonToLeagues link: from a Season to a League in it, by the league's organiser.
+pub fn validate_season_to_leagues_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be a Season ActionHash
+    let Some(season_id) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("SeasonToLeagues base must be a Season ActionHash".into()));
+    };
+    // Target Check: Must be a League of that season
+    let Some(league_id) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("SeasonToLeagues target must be a League ActionHash".into()));
+    };
+    let Some(league) = must_get_entry::<League>(&league_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("SeasonToLeagues target is not a League entry".into()));
+    };
+    if league.season_id != season_id {
+        return Ok(ValidateCallbackResult::Invalid("SeasonToLeagues base must be the league's season".into()));
+    }
+    // Author Check: Only the organiser lists their league
+    if create_link.author != league.organiser {
+        return Ok(ValidateCallbackResult::Invalid("Author of SeasonToLeagues link must be the league organiser".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a LeagueToGames link: from a League to a game played for one of its
+// fixtures. The game must be between that fixture's players, in their scheduled
+// sides, and created during the league's season.
+pub fn validate_league_to_games_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    let Ok(tag) = LeagueFixtureTag::from_link_tag(&create_link.tag) else {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames tag must be a LeagueFixtureTag".into()));
+    };
+    // Base Check: Must be a League ActionHash
+    let Some(league_id) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames base must be a League ActionHash".into()));
+    };
+    let Some(league) = must_get_entry::<League>(&league_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames base is not a League entry".into()));
+    };
+    // Target Check: Must be the original create action of a Game
+    let Some(game_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames target must be a Game ActionHash".into()));
+    };
+    let Some(game) = must_get_entry::<Game>(&game_hash)? else {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames target is not a Game entry".into()));
+    };
+
+    // Fixture Check: the tag names a scheduled fixture between the game's players
+    let schedule = round_robin_schedule(&league.members);
+    let Some((player_1, player_2)) = schedule.get(tag.round as usize).and_then(|round| round.get(tag.index as usize)) else {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames tag is not a fixture of this league".into()));
+    };
+    if game.player_1 != *player_1 || game.player_2.as_ref() != Some(player_2) {
+        return Ok(ValidateCallbackResult::Invalid("LeagueToGames game is not between the fixture's players".into()));
+    }
+    let Some(season) = must_get_entry::<Season>(&league.season_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("League season_id does not point to a Season entry".into()));
+    };
+    if !season.contains(game.created_at) {
+        return Ok(ValidateCallbackResult::Invalid("League games must be created during the league's season".into()));
+    }
+    // Author Check: One of the fixture's players
+    if create_link.author != *player_1 && create_link.author != *player_2 {
+        return Ok(ValidateCallbackResult::Invalid("Author of LeagueToGames link must be one of the fixture's players".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -11,9 +11,30 @@ pub use score::Score;
 pub mod statistics;
 pub use statistics::Statistics;
 pub mod presence;
-pub use presence::Presence;
+pub use presence::{Presence, OnlineStatus};
 pub mod anchor_path;
 pub use anchor_path::AnchorPath;
+pub mod match_result;
+pub use match_result::{MatchResult, CoSignedMatchResult};
+pub mod match_replay;
+pub use match_replay::MatchReplay;
+pub mod rating;
+pub use rating::Rating;
+pub mod glicko2;
+pub mod chat_message;
+pub use chat_message::ChatMessage;
+pub mod direct_message;
+pub use direct_message::DirectMessage;
+pub mod invitation;
+pub use invitation::{Invitation, InvitationStatus};
+pub mod tournament;
+pub use tournament::{Tournament, TournamentStatus};
+pub mod league;
+pub use league::{Season, League};
+pub mod match_series;
+pub use match_series::{MatchSeries, SeriesStatus};
+pub mod properties;
+pub use properties::{DnaProperties, PresenceMode};
 
 // Import validation functions for entries
 pub mod game_validation;
@@ -21,6 +42,15 @@ pub mod player_validation;
 pub mod score_validation; // Will be modified below
 pub mod statistics_validation; // Will be modified below
 pub mod presence_validation;
+pub mod match_result_validation;
+pub mod match_replay_validation;
+pub mod rating_validation;
+pub mod chat_message_validation;
+pub mod direct_message_validation;
+pub mod invitation_validation;
+pub mod tournament_validation;
+pub mod league_validation;
+pub mod match_series_validation;
 
 // Import utils like anchor_for (used only by link validation helpers below)
 pub mod utils;
@@ -42,6 +72,24 @@ pub enum EntryTypes {
     Presence(Presence),
     #[entry_type(visibility = "public")]
     AnchorPath(AnchorPath),
+    #[entry_type(visibility = "public")]
+    MatchReplay(MatchReplay),
+    #[entry_type(visibility = "public")]
+    Rating(Rating),
+    #[entry_type(visibility = "public")]
+    ChatMessage(ChatMessage),
+    #[entry_type(visibility = "public")]
+    DirectMessage(DirectMessage),
+    #[entry_type(visibility = "public")]
+    Invitation(Invitation),
+    #[entry_type(visibility = "public")]
+    Tournament(Tournament),
+    #[entry_type(visibility = "public")]
+    Season(Season),
+    #[entry_type(visibility = "public")]
+    League(League),
+    #[entry_type(visibility = "public")]
+    MatchSeries(MatchSeries),
 }
 
 // Define LinkTypes enum with Serde derives
@@ -60,6 +108,25 @@ pub enum LinkTypes {
     PlayerToScores,
     Presence,
     AllPlayersAnchorToAgentPubKey, // For linking the "all_players" anchor to each player's AgentPubKey
+    GameToReplay,
+    PlayerToRating, // AgentPubKey -> original Rating create action
+    RatingUpdates,  // Original Rating create action -> each update
+    MatchmakingQueue, // "matchmaking_queue" anchor -> queued AgentPubKey
+    GameBucketToGames, // Daily "games.YYYY-MM-DD" anchor -> original Game create action
+    GameStatusIndex,   // "games.waiting" / "games.in_progress" anchor -> Game action in that status
+    ChatBucketToMessages, // Hourly "chat.YYYY-MM-DD.HH" anchor -> ChatMessage create action
+    GameToChatMessages,   // Original Game create action -> persisted in-game ChatMessage
+    RecipientToDirectMessages, // Recipient AgentPubKey -> undelivered DirectMessage (offline inbox)
+    InviteeToInvitations,      // Invitee AgentPubKey -> original Invitation create action
+    GameToSpectators,          // Original Game create action -> AgentPubKey watching it
+    TournamentToPlayers,       // Original Tournament create action -> registered AgentPubKey
+    TournamentToGames,         // Original Tournament create action -> original Game create action of a bracket match
+    AllTournaments,            // "tournaments" anchor -> original Tournament create action
+    AllSeasons,                // "seasons" anchor -> Season create action
+    SeasonToLeagues,           // Season create action -> League create action in that season
+    LeagueToGames,             // League create action -> original Game create action of a fixture
+    PlayerToSeries,            // AgentPubKey -> original MatchSeries create action they play in
+    SeriesToGames,             // Original MatchSeries create action -> original Game create action of each series game
 }
 
 
@@ -87,6 +154,15 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                                             EntryTypes::Statistics(statistics) => statistics_validation::validate_create_statistics(signed_action, statistics),
                                             EntryTypes::Presence(presence) => presence_validation::validate_create_presence(signed_action, presence),
                                             EntryTypes::AnchorPath(_) => Ok(ValidateCallbackResult::Valid), // Anchor paths are structural
+                                            EntryTypes::MatchReplay(replay) => match_replay_validation::validate_create_match_replay(signed_action, replay),
+                                            EntryTypes::Rating(rating) => rating_validation::validate_create_rating(signed_action, rating),
+                                            EntryTypes::ChatMessage(message) => chat_message_validation::validate_create_chat_message(signed_action, message),
+                                            EntryTypes::DirectMessage(message) => direct_message_validation::validate_create_direct_message(signed_action, message),
+                                            EntryTypes::Invitation(invitation) => invitation_validation::validate_create_invitation(signed_action, invitation),
+                                            EntryTypes::Tournament(tournament) => tournament_validation::validate_create_tournament(signed_action, tournament),
+                                            EntryTypes::Season(season) => league_validation::validate_create_season(signed_action, season),
+                                            EntryTypes::League(league) => league_validation::validate_create_league(signed_action, league),
+                                            EntryTypes::MatchSeries(series) => match_series_validation::validate_create_match_series(signed_action, series),
                                         }
                                     }
                                     None => Ok(ValidateCallbackResult::Valid), // Unknown entry type to this zome
@@ -97,33 +173,64 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     } else { Ok(ValidateCallbackResult::Valid) } // Not an app entry
                 }
                 // --- UPDATE ENTRY ---
-                 Action::Update(update) => {
-                    // *** REMOVED GET CALL ***
-                    // Holochain's validation system implicitly handles fetching the original
-                    // entry and calling the appropriate specific validation function
-                    // (e.g., validate_update_entry_game) which receives both old and new states.
-                    // We rely on that mechanism. If validation logic MUST be here, it can only
-                    // check the `update` action properties and the `record`'s *new* entry,
-                    // but cannot compare with the old state fetched via get().
-                    // The specific update validators (e.g., game_validation::validate_update_game)
-                    // are correctly defined to receive the original state from Holochain.
-                    // We might need to explicitly register these specific validators depending on HDK version.
-                    // For now, assume Holochain dispatches correctly based on entry type.
-                    // Minimal validation possible here without get(): Check author, timestamp?
-                    // Let's trust the specific validators called by Holochain.
-                    debug!("ValidationOp::Update for action {:?}: Validation delegated to specific entry type update validator.", update.original_action_address);
-                    Ok(ValidateCallbackResult::Valid) // Pass here, rely on specific callbacks triggered by HC
+                Action::Update(update) => {
+                    // Resolve the entry being updated so the per-type validators can compare old vs new state.
+                    let EntryType::App(app_entry_type) = update.entry_type.clone() else {
+                        return Ok(ValidateCallbackResult::Valid); // Not an app entry
+                    };
+                    let Some(entry) = record.entry().as_option() else {
+                        return Ok(ValidateCallbackResult::Invalid("Update action Record is missing Entry".to_string()));
+                    };
+                    let Some(updated_entry) = EntryTypes::deserialize_from_type(app_entry_type.zome_index, app_entry_type.entry_index, entry)? else {
+                        return Ok(ValidateCallbackResult::Valid); // Unknown entry type to this zome
+                    };
+                    let original_record = must_get_valid_record(update.original_action_address.clone())?;
+                    let Some(original_entry) = entry_types_for_record(&original_record)? else {
+                        return Ok(ValidateCallbackResult::Invalid("Original record for update is not an entry of this zome".to_string()));
+                    };
+                    match (updated_entry, original_entry) {
+                        (EntryTypes::Game(game), EntryTypes::Game(original_game)) => game_validation::validate_update_game(signed_action, game, &original_game),
+                        (EntryTypes::Player(player), EntryTypes::Player(original_player)) => player_validation::validate_update_player(signed_action, player, &original_player),
+                        (EntryTypes::Score(score), EntryTypes::Score(original_score)) => score_validation::validate_update_score(signed_action, score, &original_score),
+                        (EntryTypes::Statistics(statistics), EntryTypes::Statistics(original_statistics)) => statistics_validation::validate_update_statistics(signed_action, statistics, &original_statistics),
+                        (EntryTypes::Presence(presence), EntryTypes::Presence(original_presence)) => presence_validation::validate_update_presence(signed_action, presence, &original_presence),
+                        (EntryTypes::MatchReplay(replay), EntryTypes::MatchReplay(original_replay)) => match_replay_validation::validate_update_match_replay(signed_action, replay, &original_replay),
+                        (EntryTypes::Rating(rating), EntryTypes::Rating(original_rating)) => rating_validation::validate_update_rating(signed_action, rating, &original_rating),
+                        (EntryTypes::ChatMessage(message), EntryTypes::ChatMessage(original_message)) => chat_message_validation::validate_update_chat_message(signed_action, message, &original_message),
+                        (EntryTypes::DirectMessage(message), EntryTypes::DirectMessage(original_message)) => direct_message_validation::validate_update_direct_message(signed_action, message, &original_message),
+                        (EntryTypes::Invitation(invitation), EntryTypes::Invitation(original_invitation)) => invitation_validation::validate_update_invitation(signed_action, invitation, &original_invitation),
+                        (EntryTypes::Tournament(tournament), EntryTypes::Tournament(original_tournament)) => tournament_validation::validate_update_tournament(signed_action, tournament, &original_tournament),
+                        (EntryTypes::Season(season), EntryTypes::Season(original_season)) => league_validation::validate_update_season(signed_action, season, &original_season),
+                        (EntryTypes::League(league), EntryTypes::League(original_league)) => league_validation::validate_update_league(signed_action, league, &original_league),
+                        (EntryTypes::MatchSeries(series), EntryTypes::MatchSeries(original_series)) => match_series_validation::validate_update_match_series(signed_action, series, &original_series),
+                        (EntryTypes::AnchorPath(_), EntryTypes::AnchorPath(_)) => Ok(ValidateCallbackResult::Invalid("Anchor paths cannot be updated".to_string())),
+                        _ => Ok(ValidateCallbackResult::Invalid("Update cannot change the entry type of the original entry".to_string())),
+                    }
                 }
                 // --- DELETE ENTRY ---
                 Action::Delete(delete) => {
-                    // *** REMOVED GET CALL ***
-                    // Similar to Update, Holochain handles fetching the original entry being
-                    // deleted and calls the specific delete validator (e.g., validate_delete_entry_game).
-                    // Rely on that mechanism.
-                    // The specific delete validators (e.g., game_validation::validate_delete_game)
-                    // are correctly defined to receive the original state from Holochain.
-                    debug!("ValidationOp::Delete for deletes_address {:?}: Validation delegated to specific entry type delete validator.", delete.deletes_address);
-                    Ok(ValidateCallbackResult::Valid) // Pass here, rely on specific callbacks triggered by HC
+                    // Resolve the entry being deleted so the per-type validators can check who may delete it.
+                    let original_record = must_get_valid_record(delete.deletes_address.clone())?;
+                    let Some(original_entry) = entry_types_for_record(&original_record)? else {
+                        return Ok(ValidateCallbackResult::Valid); // Not an app entry of this zome
+                    };
+                    match original_entry {
+                        EntryTypes::Game(original_game) => game_validation::validate_delete_game(signed_action, original_game),
+                        EntryTypes::Player(original_player) => player_validation::validate_delete_player(signed_action, original_player),
+                        EntryTypes::Score(original_score) => score_validation::validate_delete_score(signed_action, original_score),
+                        EntryTypes::Statistics(original_statistics) => statistics_validation::validate_delete_statistics(signed_action, original_statistics),
+                        EntryTypes::Presence(original_presence) => presence_validation::validate_delete_presence(signed_action, original_presence),
+                        EntryTypes::MatchReplay(original_replay) => match_replay_validation::validate_delete_match_replay(signed_action, original_replay),
+                        EntryTypes::Rating(original_rating) => rating_validation::validate_delete_rating(signed_action, original_rating),
+                        EntryTypes::ChatMessage(original_message) => chat_message_validation::validate_delete_chat_message(signed_action, original_message),
+                        EntryTypes::DirectMessage(original_message) => direct_message_validation::validate_delete_direct_message(signed_action, original_message),
+                        EntryTypes::Invitation(original_invitation) => invitation_validation::validate_delete_invitation(signed_action, original_invitation),
+                        EntryTypes::Tournament(original_tournament) => tournament_validation::validate_delete_tournament(signed_action, original_tournament),
+                        EntryTypes::Season(original_season) => league_validation::validate_delete_season(signed_action, original_season),
+                        EntryTypes::League(original_league) => league_validation::validate_delete_league(signed_action, original_league),
+                        EntryTypes::MatchSeries(original_series) => match_series_validation::validate_delete_match_series(signed_action, original_series),
+                        EntryTypes::AnchorPath(_) => Ok(ValidateCallbackResult::Invalid("Anchor paths cannot be deleted".to_string())),
+                    }
                 }
                 // --- CREATE LINK ---
                 Action::CreateLink(create_link) => {
@@ -141,7 +248,26 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                                 LinkTypes::PlayerNameToPlayer => validate_playername_to_player_link(&create_link),
                                 LinkTypes::PlayerUpdates => validate_player_updates_link(&create_link),
                                 LinkTypes::PlayerToScores => validate_player_to_scores_link(&create_link),
-                                LinkTypes::Presence => validate_presence_link(&create_link),
+                                LinkTypes::Presence => presence_validation::validate_create_presence_link(&create_link),
+                                LinkTypes::GameToReplay => match_replay_validation::validate_game_to_replay_link(&create_link),
+                                LinkTypes::PlayerToRating => validate_player_to_rating_link(&create_link),
+                                LinkTypes::RatingUpdates => validate_rating_updates_link(&create_link),
+                                LinkTypes::MatchmakingQueue => validate_matchmaking_queue_link(&create_link),
+                                LinkTypes::GameBucketToGames => validate_game_bucket_to_games_link(&create_link),
+                                LinkTypes::GameStatusIndex => game_validation::validate_game_status_index_link(&create_link),
+                                LinkTypes::ChatBucketToMessages => chat_message_validation::validate_chat_bucket_to_messages_link(&create_link),
+                                LinkTypes::GameToChatMessages => chat_message_validation::validate_game_to_chat_messages_link(&create_link),
+                                LinkTypes::RecipientToDirectMessages => direct_message_validation::validate_create_recipient_to_direct_messages_link(&create_link),
+                                LinkTypes::InviteeToInvitations => invitation_validation::validate_create_invitee_to_invitations_link(&create_link),
+                                LinkTypes::GameToSpectators => game_validation::validate_game_to_spectators_link(&create_link),
+                                LinkTypes::TournamentToPlayers => tournament_validation::validate_tournament_to_players_link(&create_link),
+                                LinkTypes::TournamentToGames => tournament_validation::validate_tournament_to_games_link(&create_link),
+                                LinkTypes::AllTournaments => validate_all_tournaments_link(&create_link),
+                                LinkTypes::AllSeasons => validate_all_seasons_link(&create_link),
+                                LinkTypes::SeasonToLeagues => league_validation::validate_season_to_leagues_link(&create_link),
+                                LinkTypes::LeagueToGames => league_validation::validate_league_to_games_link(&create_link),
+                                LinkTypes::PlayerToSeries => match_series_validation::validate_player_to_series_link(&create_link),
+                                LinkTypes::SeriesToGames => match_series_validation::validate_series_to_games_link(&create_link),
                                 LinkTypes::AllPlayersAnchorToAgentPubKey => {
                                     // Base must be an EntryHash (the anchor)
                                     if create_link.base_address.clone().into_entry_hash().is_none() {
@@ -161,11 +287,22 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 }
                  // --- DELETE LINK ---
                  Action::DeleteLink(delete_link) => {
-                     // *** REMOVED GET CALL to check original CreateLink author ***
-                     // Rely on Holochain's default validation which typically enforces
-                     // that only the original author of the CreateLink action can create
-                     // the corresponding DeleteLink action.
-                     debug!("ValidationOp::DeleteLink for link_add_address {:?}: Relying on default author validation.", delete_link.link_add_address);
+                     // Presence links can be pruned by anyone once stale, and inbox/invitation links are
+                     // removed by their recipient, so those have their own checks. Every other link
+                     // can only be deleted by the agent who created it; Holochain does not enforce this.
+                     let create_link_action = must_get_action(delete_link.link_add_address.clone())?;
+                     let Action::CreateLink(create_link) = create_link_action.action() else {
+                         return Ok(ValidateCallbackResult::Invalid("DeleteLink must reference a CreateLink action".to_string()));
+                     };
+                     match LinkTypes::from_type(create_link.zome_index, create_link.link_type) {
+                         Ok(Some(LinkTypes::Presence)) => return presence_validation::validate_delete_presence_link(signed_action, create_link),
+                         Ok(Some(LinkTypes::RecipientToDirectMessages)) => return direct_message_validation::validate_delete_recipient_to_direct_messages_link(signed_action, create_link),
+                         Ok(Some(LinkTypes::InviteeToInvitations)) => return invitation_validation::validate_delete_invitee_to_invitations_link(signed_action, create_link),
+                         _ => {}
+                     }
+                     if delete_link.author != create_link.author {
+                         return Ok(ValidateCallbackResult::Invalid("Only the author of a link can delete it".to_string()));
+                     }
                      Ok(ValidateCallbackResult::Valid)
                  }
                 // --- Other Actions ---
@@ -178,6 +315,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     }
 }
 
+// Deserialize the app entry carried by a record (e.g. the original of an update/delete).
+// Returns None for non-app entries or entries belonging to another zome.
+fn entry_types_for_record(record: &Record) -> ExternResult<Option<EntryTypes>> {
+    let Some(EntryType::App(app_entry_type)) = record.action().entry_type() else {
+        return Ok(None);
+    };
+    let Some(entry) = record.entry().as_option() else {
+        return Ok(None);
+    };
+    EntryTypes::deserialize_from_type(app_entry_type.zome_index, app_entry_type.entry_index, entry)
+}
+
 // --- Simplified Link Validations (No `get` calls inside) ---
 
 fn validate_gameid_to_game_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
@@ -196,12 +345,20 @@ fn validate_player1_to_game_link(create_link: &CreateLink) -> ExternResult<Valid
     let base_agent = create_link.base_address.clone().into_agent_pub_key()
          .ok_or(wasm_error!(WasmErrorInner::Guest("Player1ToGames base must be an AgentPubKey".into())))?;
     // Target Check: Must be ActionHash
-    if create_link.target_address.clone().into_action_hash().is_none() {
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
         return Ok(ValidateCallbackResult::Invalid("Player1ToGames target must be an ActionHash".into()));
-    }
-    // Author Check: Must be the Agent from the base address
+    };
+    // Author Check: Must be the Agent from the base address, except for tournament
+    // games, which the organiser or the other player may set up (see tournament_validation.rs)
     if create_link.author != base_agent {
-         return Ok(ValidateCallbackResult::Invalid("Author of Player1ToGames link must be Player 1".into()));
+        let game_record = must_get_valid_record(target_hash)?;
+        let game = game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        match game {
+            Some(game) if game.tournament_id.is_some() && game.player_1 == base_agent => {
+                return tournament_validation::validate_tournament_game_author(&create_link.author, &game);
+            }
+            _ => return Ok(ValidateCallbackResult::Invalid("Author of Player1ToGames link must be Player 1".into())),
+        }
     }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -316,15 +473,89 @@ fn validate_player_to_scores_link(create_link: &CreateLink) -> ExternResult<Vali
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_presence_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
-    // Base Check: Must be AnyLinkableHash (EntryHash)
+fn validate_player_to_rating_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an AgentPubKey
+    let base_agent = create_link.base_address.clone().into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest("PlayerToRating base must be an AgentPubKey".into())))?;
+    // Target Check: Must be ActionHash
+    if create_link.target_address.clone().into_action_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("PlayerToRating target must be a Rating ActionHash".into()));
+    }
+    // Author Check: Only the player links their own rating
+    if create_link.author != base_agent {
+        return Ok(ValidateCallbackResult::Invalid("Author of PlayerToRating link must be the player".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_rating_updates_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be ActionHash
+    if create_link.base_address.clone().into_action_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("RatingUpdates base must be an ActionHash".into()));
+    }
+    // Target Check: Must be ActionHash
+    if create_link.target_address.clone().into_action_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("RatingUpdates target must be an ActionHash".into()));
+    }
+    // Note: Cannot validate author or target relationship without get calls
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_matchmaking_queue_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an EntryHash (the queue anchor)
+    if create_link.base_address.clone().into_entry_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("MatchmakingQueue base must be the 'matchmaking_queue' anchor hash".into()));
+    }
+    // Target Check: Must be an AgentPubKey
+    let target_agent = create_link.target_address.clone().into_agent_pub_key()
+        .ok_or(wasm_error!(WasmErrorInner::Guest("MatchmakingQueue target must be an AgentPubKey".into())))?;
+    // Author Check: Agents can only queue themselves
+    if create_link.author != target_agent {
+        return Ok(ValidateCallbackResult::Invalid("Agents can only add themselves to the matchmaking queue".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_all_tournaments_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an EntryHash (the "tournaments" anchor)
+    if create_link.base_address.clone().into_entry_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("AllTournaments base must be the 'tournaments' anchor hash".into()));
+    }
+    // Target Check: Must be ActionHash
+    if create_link.target_address.clone().into_action_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("AllTournaments target must be a Tournament ActionHash".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_all_seasons_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an EntryHash (the "seasons" anchor)
     if create_link.base_address.clone().into_entry_hash().is_none() {
-         return Ok(ValidateCallbackResult::Invalid("Base for Presence link must be 'presence' anchor hash".into()));
+        return Ok(ValidateCallbackResult::Invalid("AllSeasons base must be the 'seasons' anchor hash".into()));
     }
     // Target Check: Must be ActionHash
     if create_link.target_address.clone().into_action_hash().is_none() {
-        return Ok(ValidateCallbackResult::Invalid("Presence link target must be an ActionHash".into()));
+        return Ok(ValidateCallbackResult::Invalid("AllSeasons target must be a Season ActionHash".into()));
     }
-    // Note: Cannot validate author without get call to retrieve Presence entry's agent_pubkey
     Ok(ValidateCallbackResult::Valid)
-}
\ No newline at end of file
+}
+
+fn validate_game_bucket_to_games_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an EntryHash (the daily bucket anchor)
+    let Some(base_hash) = create_link.base_address.clone().into_entry_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameBucketToGames base must be a daily 'games.YYYY-MM-DD' anchor hash".into()));
+    };
+    // Target Check: Must be the ActionHash of a Game
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameBucketToGames target must be a Game ActionHash".into()));
+    };
+    let game_record = must_get_valid_record(target_hash)?;
+    let Some(game) = game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("GameBucketToGames target is not a Game entry".into()));
+    };
+    // Bucket Check: Must be the bucket of the game's creation day
+    if base_hash != Path::from(utils::game_bucket_name(utils::day_of(game.created_at))).path_entry_hash()? {
+        return Ok(ValidateCallbackResult::Invalid("GameBucketToGames base is not the bucket of the game's created_at day".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
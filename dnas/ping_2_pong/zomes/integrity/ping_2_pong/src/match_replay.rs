@@ -0,0 +1,32 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/match_replay.rs
+use hdk::prelude::*;
+use crate::match_result::CoSignedMatchResult;
+
+// One entry of a replay timeline. `dt` is the number of ticks since the previous
+// event (delta encoding keeps the serialized integers small), and repeated paddle
+// inputs are dropped by the encoder, so the log stays compact.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ReplayEvent {
+    Input { dt: u32, player_2: bool, paddle_y: u32 },          // Paddle top edge in pixels
+    BallReset { dt: u32, x: u32, y: u32, dx: i32, dy: i32 },   // Ball re-served (pixels, pixels/tick)
+    Goal { dt: u32, player_2: bool },                          // Point scored by Player 1 or Player 2
+}
+
+impl ReplayEvent {
+    pub fn dt(&self) -> u32 {
+        match self {
+            ReplayEvent::Input { dt, .. } | ReplayEvent::BallReset { dt, .. } | ReplayEvent::Goal { dt, .. } => *dt,
+        }
+    }
+}
+
+// Timeline of a finished game, committed at game end and linked from the game hash.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct MatchReplay {
+    pub game_id: ActionHash,         // Original Game create action
+    pub result: CoSignedMatchResult, // Result both players signed; the goals must agree with it
+    pub final_tick: u64,             // Tick of the last event
+    pub events: Vec<ReplayEvent>,
+    pub created_at: Timestamp,
+}
@@ -0,0 +1,113 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/match_replay_validation.rs
+use hdk::prelude::*;
+use crate::match_replay::{MatchReplay, ReplayEvent};
+use crate::match_result_validation::{validate_co_signed_result, validate_result_references_game};
+
+// Upper bound on the number of events in a single replay entry.
+pub const MAX_REPLAY_EVENTS: usize = 50_000;
+// Upper bound on the length of a replay: one hour at 60 ticks per second.
+pub const MAX_REPLAY_TICKS: u64 = 216_000;
+// Paddle positions are pixel offsets within the 600px high canvas.
+const MAX_PADDLE_Y: u32 = 600;
+
+// Validate creation of a MatchReplay entry.
+pub fn validate_create_match_replay(
+    action: &SignedActionHashed,
+    replay: MatchReplay,
+) -> ExternResult<ValidateCallbackResult> {
+    let result = &replay.result.result;
+
+    // 1. Check Game: the replay must belong to the game of its co-signed result.
+    if result.game_id != replay.game_id {
+        return Ok(ValidateCallbackResult::Invalid("Replay game_id does not match its match result".to_string()));
+    }
+    let signatures_check = validate_co_signed_result(&replay.result)?;
+    if signatures_check != ValidateCallbackResult::Valid {
+        return Ok(signatures_check);
+    }
+    let game_check = validate_result_references_game(&replay.result)?;
+    if game_check != ValidateCallbackResult::Valid {
+        return Ok(game_check);
+    }
+
+    // 2. Check Author: only one of the two players can record the replay.
+    let author = action.action().author();
+    if *author != result.player_1 && *author != result.player_2 {
+        return Ok(ValidateCallbackResult::Invalid("Replay can only be recorded by one of the players".to_string()));
+    }
+
+    // 3. Check Size and Contents.
+    if replay.events.len() > MAX_REPLAY_EVENTS {
+        return Ok(ValidateCallbackResult::Invalid(format!("Replay has too many events (max {})", MAX_REPLAY_EVENTS)));
+    }
+    let mut tick: u64 = 0;
+    let mut goals_1: u32 = 0;
+    let mut goals_2: u32 = 0;
+    for event in &replay.events {
+        tick += event.dt() as u64;
+        match event {
+            ReplayEvent::Input { paddle_y, .. } if *paddle_y > MAX_PADDLE_Y => {
+                return Ok(ValidateCallbackResult::Invalid("Replay paddle input is outside the canvas".to_string()));
+            }
+            ReplayEvent::Goal { player_2: false, .. } => goals_1 += 1,
+            ReplayEvent::Goal { player_2: true, .. } => goals_2 += 1,
+            _ => {}
+        }
+    }
+    if replay.final_tick > MAX_REPLAY_TICKS {
+        return Ok(ValidateCallbackResult::Invalid(format!("Replay is too long (max {} ticks)", MAX_REPLAY_TICKS)));
+    }
+    if tick != replay.final_tick {
+        return Ok(ValidateCallbackResult::Invalid("Replay final_tick does not match its events".to_string()));
+    }
+
+    // 4. Check Goals: the timeline must end in the agreed score.
+    if goals_1 != result.score1 || goals_2 != result.score2 {
+        return Ok(ValidateCallbackResult::Invalid("Replay goals do not match the co-signed match result".to_string()));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Replays are immutable once recorded.
+pub fn validate_update_match_replay(
+    _action: &SignedActionHashed,
+    _updated_replay: MatchReplay,
+    _original_replay: &MatchReplay,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("MatchReplay entries cannot be updated".to_string()))
+}
+
+// Replays are kept so matches can be disputed later.
+pub fn validate_delete_match_replay(
+    _action: &SignedActionHashed,
+    _original_replay: MatchReplay,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("MatchReplay entries cannot be deleted".to_string()))
+}
+
+// Validate a GameToReplay link: from a Game to the MatchReplay recorded for it,
+// created by one of the game's players.
+pub fn validate_game_to_replay_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be a Game ActionHash
+    let Some(game_hash) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToReplay base must be a Game ActionHash".into()));
+    };
+    // Target Check: Must be a MatchReplay of this game
+    let Some(replay_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToReplay target must be a MatchReplay ActionHash".into()));
+    };
+    let replay_record = must_get_valid_record(replay_hash)?;
+    let Ok(Some(replay)) = replay_record.entry().to_app_option::<MatchReplay>() else {
+        return Ok(ValidateCallbackResult::Invalid("GameToReplay target is not a MatchReplay entry".into()));
+    };
+    if replay.game_id != game_hash {
+        return Ok(ValidateCallbackResult::Invalid("GameToReplay target is the replay of a different game".into()));
+    }
+    // Author Check: The replay's result was validated against the game, so its players are the game's players
+    let result = &replay.result.result;
+    if create_link.author != result.player_1 && create_link.author != result.player_2 {
+        return Ok(ValidateCallbackResult::Invalid("Only a player of the game can link its replay".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
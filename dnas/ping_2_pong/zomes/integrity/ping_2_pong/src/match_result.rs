@@ -0,0 +1,26 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/match_result.rs
+use hdk::prelude::*;
+
+// The final result of a game. Both players sign this exact struct, so the
+// serialized bytes (and therefore the signatures) only match if they agree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatchResult {
+    pub game_id: ActionHash,       // Original Game create action
+    pub join_action: ActionHash,   // Game update in which player_2 joined (Waiting -> InProgress)
+    pub player_1: AgentPubKey,
+    pub player_2: AgentPubKey,
+    pub score1: u32,               // Player 1's points
+    pub score2: u32,               // Player 2's points
+    pub winner: Option<AgentPubKey>, // None only for a draw
+    pub player_1_rating: ActionHash, // Player 1's Rating revision going into this game
+    pub player_2_rating: ActionHash, // Player 2's Rating revision going into this game
+}
+
+// A MatchResult carrying both players' signatures over the same result.
+// Required on the final Game update and on every Score entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CoSignedMatchResult {
+    pub result: MatchResult,
+    pub player_1_signature: Signature,
+    pub player_2_signature: Signature,
+}
@@ -0,0 +1,98 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/match_result_validation.rs
+use hdk::prelude::*;
+use crate::game::{Game, GameStatus};
+use crate::match_result::CoSignedMatchResult;
+use core::cmp::Ordering;
+
+// Validate that a co-signed result is internally consistent and signed by both players.
+pub fn validate_co_signed_result(
+    co_signed: &CoSignedMatchResult,
+) -> ExternResult<ValidateCallbackResult> {
+    let result = &co_signed.result;
+
+    // 1. Check Players: must be two different agents.
+    if result.player_1 == result.player_2 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result players must be two different agents".to_string(),
+        ));
+    }
+
+    // 2. Check Winner: must follow from the scores.
+    let expected_winner = match result.score1.cmp(&result.score2) {
+        Ordering::Greater => Some(result.player_1.clone()),
+        Ordering::Less => Some(result.player_2.clone()),
+        Ordering::Equal => None,
+    };
+    if result.winner != expected_winner {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result winner does not match the scores".to_string(),
+        ));
+    }
+
+    // 3. Check Signatures: both players must have signed this exact result.
+    if !verify_signature(result.player_1.clone(), co_signed.player_1_signature.clone(), result)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result is missing a valid signature from Player 1".to_string(),
+        ));
+    }
+    if !verify_signature(result.player_2.clone(), co_signed.player_2_signature.clone(), result)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result is missing a valid signature from Player 2".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate that a co-signed result refers to an existing Game created by the same Player 1,
+// and that Player 2 is the agent who actually joined it. The join update was itself
+// validated as authored by Player 2, so a result cannot name an agent who never took the seat.
+// Uses must_get_valid_record on the create and join actions, which is deterministic.
+pub fn validate_result_references_game(
+    co_signed: &CoSignedMatchResult,
+) -> ExternResult<ValidateCallbackResult> {
+    let result = &co_signed.result;
+    let game_record = must_get_valid_record(result.game_id.clone())?;
+    if !matches!(game_record.action(), Action::Create(_)) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result game_id must be the original Game create action".to_string(),
+        ));
+    }
+    let original_game = match game_record.entry().to_app_option::<Game>() {
+        Ok(Some(game)) => game,
+        _ => return Ok(ValidateCallbackResult::Invalid(
+            "Match result game_id does not point to a Game entry".to_string(),
+        )),
+    };
+    if original_game.player_1 != result.player_1 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result Player 1 does not match the Game".to_string(),
+        ));
+    }
+
+    // The join is the only transition out of Waiting into InProgress, made directly on the create.
+    let join_record = must_get_valid_record(result.join_action.clone())?;
+    match join_record.action() {
+        Action::Update(update) if update.original_action_address == result.game_id => {}
+        _ => return Ok(ValidateCallbackResult::Invalid(
+            "Match result join_action must be an update of the Game create action".to_string(),
+        )),
+    }
+    let joined_game = match join_record.entry().to_app_option::<Game>() {
+        Ok(Some(game)) => game,
+        _ => return Ok(ValidateCallbackResult::Invalid(
+            "Match result join_action does not point to a Game entry".to_string(),
+        )),
+    };
+    if joined_game.game_status != GameStatus::InProgress {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result join_action is not the join of the Game".to_string(),
+        ));
+    }
+    if joined_game.player_2.as_ref() != Some(&result.player_2) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match result Player 2 is not the agent who joined the Game".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
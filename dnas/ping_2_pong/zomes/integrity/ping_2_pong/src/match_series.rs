@@ -0,0 +1,124 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/match_series.rs
+use hdk::prelude::*;
+
+pub const SERIES_LENGTHS: [u32; 3] = [3, 5, 7]; // Allowed values of best_of
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum SeriesStatus {
+    InProgress, // Games are being played
+    Finished,   // A player reached wins_needed
+}
+
+// A best-of-N series between two players. Its games are ordinary invite-only Games
+// carrying the series id, linked from the series with a SeriesGameTag; players swap
+// sides every game (see `players_for_game`). The score is never stored: it is
+// derived from the linked games (see coordinator match_series.rs).
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct MatchSeries {
+    pub player_1: AgentPubKey, // Also the author (the challenger); player 1 of the first game
+    pub player_2: AgentPubKey,
+    pub best_of: u32,
+    pub created_at: Timestamp,
+    pub status: SeriesStatus,
+    pub winner: Option<AgentPubKey>,
+    pub decided_by: Option<ActionHash>, // Game action that gave the winner their deciding win
+    // Set with `winner`: the wins_needed() games the winner won, in game order, ending
+    // with the one `decided_by` points at.
+    #[serde(default)]
+    pub wins: Vec<SeriesWin>,
+}
+
+// A series game its winner won: the game's number and the Game action that decided it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SeriesWin {
+    pub game_number: u32, // As in the game's SeriesGameTag
+    pub decided_by: ActionHash,
+}
+
+impl MatchSeries {
+    pub fn wins_needed(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    // (player 1, player 2) of the series game with this number (0-based): the challenger
+    // is player 1 of the even games, the opponent of the odd ones.
+    pub fn players_for_game(&self, game_number: u32) -> (AgentPubKey, AgentPubKey) {
+        if game_number.is_multiple_of(2) {
+            (self.player_1.clone(), self.player_2.clone())
+        } else {
+            (self.player_2.clone(), self.player_1.clone())
+        }
+    }
+
+    pub fn is_player(&self, agent: &AgentPubKey) -> bool {
+        self.player_1 == *agent || self.player_2 == *agent
+    }
+}
+
+// Tag of a SeriesToGames link: which game of the series the linked game is.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct SeriesGameTag {
+    pub game_number: u32, // 0-based
+}
+
+impl SeriesGameTag {
+    pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(self.clone()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+
+    pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+        SeriesGameTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.clone().into_inner())))
+            .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(best_of: u32) -> MatchSeries {
+        MatchSeries {
+            player_1: AgentPubKey::from_raw_36(vec![1; 36]),
+            player_2: AgentPubKey::from_raw_36(vec![2; 36]),
+            best_of,
+            created_at: Timestamp::from_micros(0),
+            status: SeriesStatus::InProgress,
+            winner: None,
+            decided_by: None,
+            wins: vec![],
+        }
+    }
+
+    #[test]
+    fn wins_needed_is_a_majority_of_best_of() {
+        assert_eq!(series(3).wins_needed(), 2);
+        assert_eq!(series(5).wins_needed(), 3);
+        assert_eq!(series(7).wins_needed(), 4);
+        for best_of in SERIES_LENGTHS {
+            assert!(2 * series(best_of).wins_needed() > best_of, "best of {}", best_of);
+        }
+    }
+
+    #[test]
+    fn players_swap_sides_every_game() {
+        let series = series(5);
+        let challenger_first = (series.player_1.clone(), series.player_2.clone());
+        let opponent_first = (series.player_2.clone(), series.player_1.clone());
+        assert_eq!(series.players_for_game(0), challenger_first);
+        assert_eq!(series.players_for_game(1), opponent_first);
+        assert_eq!(series.players_for_game(2), challenger_first);
+        // Replays of drawn games go past best_of and keep alternating
+        assert_eq!(series.players_for_game(7), opponent_first);
+        assert_eq!(series.players_for_game(8), challenger_first);
+    }
+
+    #[test]
+    fn is_player_only_matches_the_two_players() {
+        let series = series(3);
+        assert!(series.is_player(&series.player_1));
+        assert!(series.is_player(&series.player_2));
+        assert!(!series.is_player(&AgentPubKey::from_raw_36(vec![3; 36])));
+    }
+}
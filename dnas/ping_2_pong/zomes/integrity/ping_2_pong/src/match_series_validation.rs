@@ -0,0 +1,213 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/match_series_validation.rs
+use hdk::prelude::*;
+use crate::match_series::{MatchSeries, SeriesStatus, SeriesGameTag, SeriesWin, SERIES_LENGTHS};
+use crate::game::{Game, GameStatus, GameVisibility};
+
+// Validate creation of a MatchSeries entry: the challenger starts it, with no result.
+pub fn validate_create_match_series(
+    action: &SignedActionHashed,
+    series: MatchSeries,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Check Players
+    if series.player_1 != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Series player_1 must be the author".to_string()));
+    }
+    if series.player_1 == series.player_2 {
+        return Ok(ValidateCallbackResult::Invalid("A player cannot play a series against themselves".to_string()));
+    }
+
+    // 2. Check Settings and Initial State
+    if !SERIES_LENGTHS.contains(&series.best_of) {
+        return Ok(ValidateCallbackResult::Invalid(format!("Series must be best of one of {:?}", SERIES_LENGTHS)));
+    }
+    if series.status != SeriesStatus::InProgress || series.winner.is_some() || series.decided_by.is_some() || !series.wins.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Series must be created in progress, without a result".to_string()));
+    }
+
+    // 3. Check Timestamp
+    if (series.created_at.as_millis() - action.action().timestamp().as_millis()).abs() > 300_000 {
+        return Ok(ValidateCallbackResult::Invalid("Series created_at is too far from the action timestamp".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// The only update is InProgress -> Finished, by either player, listing the games the
+// winner won to reach wins_needed().
+pub fn validate_update_match_series(
+    action: &SignedActionHashed,
+    updated_series: MatchSeries,
+    original_series: &MatchSeries,
+) -> ExternResult<ValidateCallbackResult> {
+    let Action::Update(update) = action.action() else {
+        return Ok(ValidateCallbackResult::Invalid("Series update must be an Update action".to_string()));
+    };
+    if original_series.status != SeriesStatus::InProgress || updated_series.status != SeriesStatus::Finished {
+        return Ok(ValidateCallbackResult::Invalid("A series can only be updated from InProgress to Finished".to_string()));
+    }
+    let unchanged = MatchSeries {
+        status: updated_series.status.clone(),
+        winner: updated_series.winner.clone(),
+        decided_by: updated_series.decided_by.clone(),
+        wins: updated_series.wins.clone(),
+        ..original_series.clone()
+    };
+    if updated_series != unchanged {
+        return Ok(ValidateCallbackResult::Invalid("Finishing a series can only set its status and result".to_string()));
+    }
+    if !original_series.is_player(action.action().author()) {
+        return Ok(ValidateCallbackResult::Invalid("Only a series player can finish it".to_string()));
+    }
+    let (Some(winner), Some(decided_by)) = (&updated_series.winner, &updated_series.decided_by) else {
+        return Ok(ValidateCallbackResult::Invalid("Finished series must name its winner and deciding game".to_string()));
+    };
+    if !original_series.is_player(winner) {
+        return Ok(ValidateCallbackResult::Invalid("Series winner must be one of its players".to_string()));
+    }
+
+    // The winner must list exactly the wins needed, in increasing game order, each a
+    // different game, the last being the deciding one
+    let wins = &updated_series.wins;
+    if wins.len() as u32 != original_series.wins_needed() {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Finished series must list the winner's {} wins", original_series.wins_needed()
+        )));
+    }
+    if wins.windows(2).any(|pair| pair[0].game_number >= pair[1].game_number) {
+        return Ok(ValidateCallbackResult::Invalid("Series wins must have distinct game numbers in increasing order".to_string()));
+    }
+    if wins.iter().enumerate().any(|(i, win)| wins[..i].iter().any(|earlier| earlier.decided_by == win.decided_by)) {
+        return Ok(ValidateCallbackResult::Invalid("Series wins must be decided by different games".to_string()));
+    }
+    if wins.last().map(|win| &win.decided_by) != Some(decided_by) {
+        return Ok(ValidateCallbackResult::Invalid("Series decided_by must be the last of its wins".to_string()));
+    }
+    for win in wins {
+        let win_check = validate_series_win(&update.original_action_address, original_series, winner, win)?;
+        if win_check != ValidateCallbackResult::Valid {
+            return Ok(win_check);
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// A win listed in a finished series: a game of this series with the sides of its game
+// number that the winner won, finished with them as the co-signed winner or walked out
+// of by the opponent. Finished, Abandoned and Canceled are final, so distinct deciding
+// actions are distinct games. Game numbers are not bounded by best_of: drawn games are
+// replayed under the next number.
+fn validate_series_win(series_id: &ActionHash, series: &MatchSeries, winner: &AgentPubKey, win: &SeriesWin) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(win.decided_by.clone())?;
+    let Some(game) = record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("Series win is not decided by a Game entry".to_string()));
+    };
+    if game.series_id.as_ref() != Some(series_id) {
+        return Ok(ValidateCallbackResult::Invalid("Series win belongs to a different series".to_string()));
+    }
+    let (player_1, player_2) = series.players_for_game(win.game_number);
+    if game.player_1 != player_1 || game.player_2.as_ref() != Some(&player_2) {
+        return Ok(ValidateCallbackResult::Invalid("Series win game does not have the sides of its game number".to_string()));
+    }
+    let won = match game.game_status {
+        GameStatus::Finished => game.result.as_ref().and_then(|r| r.result.winner.as_ref()) == Some(winner),
+        GameStatus::Abandoned | GameStatus::Canceled => record.action().author() != winner,
+        _ => false,
+    };
+    if !won {
+        return Ok(ValidateCallbackResult::Invalid("Series win was not won by the winner".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Series are part of both players' history and are never deleted.
+pub fn validate_delete_match_series(
+    _action: &SignedActionHashed,
+    _original_series: MatchSeries,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Match series cannot be deleted".to_string()))
+}
+
+fn must_get_series(series_id: &ActionHash) -> ExternResult<Option<MatchSeries>> {
+    must_get_valid_record(series_id.clone())?
+        .entry()
+        .to_app_option::<MatchSeries>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+}
+
+// A Game carrying a series_id is one of the series' games: invite-only and between
+// the series' two players.
+pub fn validate_series_game(game: &Game) -> ExternResult<ValidateCallbackResult> {
+    let Some(series_id) = &game.series_id else {
+        return Ok(ValidateCallbackResult::Invalid("Game is not part of a series".to_string()));
+    };
+    let Some(series) = must_get_series(series_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("Game series_id does not point to a MatchSeries entry".to_string()));
+    };
+    if game.visibility != GameVisibility::InviteOnly {
+        return Ok(ValidateCallbackResult::Invalid("Series games must be invite-only".to_string()));
+    }
+    let Some(player_2) = &game.player_2 else {
+        return Ok(ValidateCallbackResult::Invalid("Series games must name both players".to_string()));
+    };
+    if !series.is_player(&game.player_1) || !series.is_player(player_2) {
+        return Ok(ValidateCallbackResult::Invalid("Series games must be between the series' players".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a PlayerToSeries link: from one of the series' players to the series.
+pub fn validate_player_to_series_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be an AgentPubKey
+    let Some(player) = create_link.base_address.clone().into_agent_pub_key() else {
+        return Ok(ValidateCallbackResult::Invalid("PlayerToSeries base must be an AgentPubKey".into()));
+    };
+    // Target Check: Must be a MatchSeries that the player plays in
+    let Some(series_id) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("PlayerToSeries target must be a MatchSeries ActionHash".into()));
+    };
+    let Some(series) = must_get_series(&series_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("PlayerToSeries target is not a MatchSeries entry".into()));
+    };
+    if !series.is_player(&player) {
+        return Ok(ValidateCallbackResult::Invalid("PlayerToSeries base must be one of the series' players".into()));
+    }
+    // Author Check: Either player (the challenger links both)
+    if !series.is_player(&create_link.author) {
+        return Ok(ValidateCallbackResult::Invalid("Author of PlayerToSeries link must be a series player".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a SeriesToGames link: from a series to its game with the tagged number,
+// played with the sides that game number gets.
+pub fn validate_series_to_games_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    let Ok(tag) = SeriesGameTag::from_link_tag(&create_link.tag) else {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames tag must be a SeriesGameTag".into()));
+    };
+    // Base Check: Must be a MatchSeries ActionHash
+    let Some(series_id) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames base must be a MatchSeries ActionHash".into()));
+    };
+    let Some(series) = must_get_series(&series_id)? else {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames base is not a MatchSeries entry".into()));
+    };
+    // Target Check: Must be a Game of this series
+    let Some(game_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames target must be a Game ActionHash".into()));
+    };
+    let game_record = must_get_valid_record(game_hash)?;
+    let Some(game) = game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames target is not a Game entry".into()));
+    };
+    if game.series_id.as_ref() != Some(&series_id) {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames target game belongs to a different series".into()));
+    }
+    let (player_1, player_2) = series.players_for_game(tag.game_number);
+    if game.player_1 != player_1 || game.player_2.as_ref() != Some(&player_2) {
+        return Ok(ValidateCallbackResult::Invalid("SeriesToGames game does not have the sides of its game number".into()));
+    }
+    // Author Check: The game's player 1, who creates it
+    if create_link.author != player_1 {
+        return Ok(ValidateCallbackResult::Invalid("Author of SeriesToGames link must be the game's player 1".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -81,6 +81,3 @@ pub fn validate_delete_player(
 
     Ok(ValidateCallbackResult::Valid)
 }
-
-// FIX: Remove helper function that uses `get`
-// fn must_get_valid_record(action_hash: ActionHash) -> ExternResult<Record> { ... }
\ No newline at end of file
@@ -1,10 +1,44 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/presence.rs
 use hdk::prelude::*;
 
+// A Presence link older than this may be pruned by any agent (milliseconds).
+pub const PRESENCE_PRUNE_AFTER_MS: u64 = 60 * 60 * 1000; // 1 hour
+
+// What an agent is doing, as shown in the lobby.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum OnlineStatus {
+    Online, // Active in the app
+    Idle,   // App open but no recent interaction
+    InGame, // Playing a game
+    Away,   // No heartbeat for a while (derived when reading, never published)
+}
+
 // Presence entry to indicate recent activity.
+// Each agent keeps a single Presence record that is updated on every heartbeat.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct Presence {
     pub agent_pubkey: AgentPubKey, // The agent who is present
     pub timestamp: u64, // Milliseconds since epoch (client-generated, validated)
-}
\ No newline at end of file
+    pub status: OnlineStatus,
+}
+
+// Tag of a Presence link, mirroring the Presence it targets so the lobby
+// can read status and last-seen from get_links alone.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct PresenceTag {
+    pub status: OnlineStatus,
+    pub timestamp: u64,
+}
+
+impl PresenceTag {
+    pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(self.clone()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+
+    pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+        PresenceTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.clone().into_inner())))
+            .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+    }
+}
@@ -1,6 +1,6 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/presence_validation.rs
 use hdk::prelude::*;
-use crate::presence::Presence;
+use crate::presence::{Presence, PresenceTag, OnlineStatus, PRESENCE_PRUNE_AFTER_MS};
 
 pub fn validate_create_presence(
     action: &SignedActionHashed,
@@ -13,7 +13,12 @@ pub fn validate_create_presence(
         ));
     }
 
-    // 2. Check Timestamp plausibility (not too far in past/future)
+    // 2. Away is derived by readers from the last-seen time, never published
+    if presence.status == OnlineStatus::Away {
+        return Ok(ValidateCallbackResult::Invalid("Presence status cannot be published as Away".to_string()));
+    }
+
+    // 3. Check Timestamp plausibility (not too far in past/future)
      let action_time_ms = action.action().timestamp().as_millis(); // This is i64
      let five_minutes_ms: i64 = 300_000; // 5 * 60 * 1000
 
@@ -42,4 +47,88 @@ pub fn validate_create_presence(
     Ok(ValidateCallbackResult::Valid)
 }
 
-// No updates or deletes defined/validated for Presence entries.
\ No newline at end of file
+// Validate updating a Presence entry: only the agent themselves may refresh it.
+pub fn validate_update_presence(
+    action: &SignedActionHashed,
+    updated_presence: Presence,
+    original_presence: &Presence,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_presence.agent_pubkey != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Presence entry can only be updated by the agent it belongs to".to_string(),
+        ));
+    }
+    if updated_presence.agent_pubkey != original_presence.agent_pubkey {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Cannot change the agent_pubkey of a Presence entry".to_string(),
+        ));
+    }
+    if updated_presence.timestamp < original_presence.timestamp {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Updated presence timestamp cannot go backwards".to_string(),
+        ));
+    }
+    if updated_presence.status == OnlineStatus::Away {
+        return Ok(ValidateCallbackResult::Invalid("Presence status cannot be published as Away".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate deleting a Presence entry: only the agent themselves may remove it.
+pub fn validate_delete_presence(
+    action: &SignedActionHashed,
+    original_presence: Presence,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_presence.agent_pubkey != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Presence entry can only be deleted by the agent it belongs to".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a Presence link: it must point at the author's own Presence record and
+// its tag must mirror that record.
+pub fn validate_create_presence_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be AnyLinkableHash (EntryHash)
+    if create_link.base_address.clone().into_entry_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("Base for Presence link must be 'presence' anchor hash".into()));
+    }
+    // Target Check: Must be the ActionHash of a Presence record
+    let Some(target_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Presence link target must be an ActionHash".into()));
+    };
+    let Ok(tag) = PresenceTag::from_link_tag(&create_link.tag) else {
+        return Ok(ValidateCallbackResult::Invalid("Presence link tag must be a PresenceTag".into()));
+    };
+    let target_record = must_get_valid_record(target_hash)?;
+    let Some(presence) = target_record.entry().to_app_option::<Presence>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("Presence link target is not a Presence entry".into()));
+    };
+    // Author Check: Agents only link their own presence
+    if presence.agent_pubkey != create_link.author {
+        return Ok(ValidateCallbackResult::Invalid("Presence link author must be the agent of the Presence entry".into()));
+    }
+    if tag.status != presence.status || tag.timestamp != presence.timestamp {
+        return Ok(ValidateCallbackResult::Invalid("Presence link tag does not match the Presence entry".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate deleting a Presence link: the author replaces it on every heartbeat,
+// and anyone may prune it once it has gone stale.
+pub fn validate_delete_presence_link(
+    action: &SignedActionHashed,
+    create_link: &CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() == create_link.author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let age_ms = action.action().timestamp().as_millis().saturating_sub(create_link.timestamp.as_millis());
+    if age_ms < PRESENCE_PRUNE_AFTER_MS as i64 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the agent can delete their Presence link until it has gone stale".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
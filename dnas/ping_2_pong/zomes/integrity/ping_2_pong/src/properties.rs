@@ -0,0 +1,29 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/properties.rs
+use hdk::prelude::*;
+
+// How agents announce that they are online.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceMode {
+    // Every heartbeat updates the agent's Presence record on the DHT
+    #[default]
+    Dht,
+    // Heartbeats are sent to peers as remote signals; the Presence record is
+    // only written when the agent first joins (and refreshed before it goes stale)
+    Gossip,
+}
+
+// DNA properties, set under `integrity.properties` in dna.yaml or when installing the app.
+// Missing or malformed properties fall back to the defaults.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DnaProperties {
+    pub presence_mode: PresenceMode,
+}
+
+impl DnaProperties {
+    pub fn get() -> ExternResult<Self> {
+        let properties = dna_info()?.modifiers.properties;
+        Ok(DnaProperties::try_from(properties).unwrap_or_default())
+    }
+}
@@ -0,0 +1,28 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/rating.rs
+use hdk::prelude::*;
+use crate::match_result::CoSignedMatchResult;
+use crate::glicko2::Glicko2Rating;
+
+// Glicko-2 skill rating of a player, in the familiar Glicko scale (1500 = average).
+// There is one Rating entry per player: it is created with the default rating when
+// their first game starts (`ensure_rating`), so it exists before any rated game, and
+// updated by the player after every co-finished game (see glicko2.rs). Leaderboards
+// skip ratings with no games played. Validation recomputes each update from the
+// pre-game ratings named in the co-signed result.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Rating {
+    pub player: AgentPubKey,  // The player this rating belongs to; also the author
+    pub rating: f64,          // Rating (r)
+    pub deviation: f64,       // Rating deviation (RD): uncertainty of the rating
+    pub volatility: f64,      // Volatility (sigma): expected fluctuation of the rating
+    pub games_played: u32,    // Number of rated games
+    pub last_result: Option<CoSignedMatchResult>, // Game this revision was computed from; None before the first game
+    pub updated_at: Timestamp,
+}
+
+impl From<&Rating> for Glicko2Rating {
+    fn from(rating: &Rating) -> Self {
+        Glicko2Rating { rating: rating.rating, deviation: rating.deviation, volatility: rating.volatility }
+    }
+}
@@ -0,0 +1,146 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/rating_validation.rs
+use hdk::prelude::*;
+use crate::rating::Rating;
+use crate::glicko2::{self, GameOutcome, Glicko2Rating};
+use crate::match_result_validation::{validate_co_signed_result, validate_result_references_game};
+use crate::utils::earlier_chain_entries;
+use crate::UnitEntryTypes;
+
+// Sanity bounds for the Glicko-2 values, checked on every revision.
+pub const MIN_RATING: f64 = 0.0;
+pub const MAX_RATING: f64 = 4000.0;
+pub const MAX_DEVIATION: f64 = 350.0;
+pub const MAX_VOLATILITY: f64 = 1.0;
+// Allowed difference between a submitted rating update and the recomputed one.
+const RECOMPUTE_TOLERANCE: f64 = 1e-6;
+
+fn check_bounds(rating: &Rating) -> Option<ValidateCallbackResult> {
+    if !(rating.rating.is_finite() && (MIN_RATING..=MAX_RATING).contains(&rating.rating)) {
+        return Some(ValidateCallbackResult::Invalid(format!("Rating must be between {} and {}", MIN_RATING, MAX_RATING)));
+    }
+    if !(rating.deviation.is_finite() && rating.deviation > 0.0 && rating.deviation <= MAX_DEVIATION) {
+        return Some(ValidateCallbackResult::Invalid(format!("Rating deviation must be in (0, {}]", MAX_DEVIATION)));
+    }
+    if !(rating.volatility.is_finite() && rating.volatility > 0.0 && rating.volatility <= MAX_VOLATILITY) {
+        return Some(ValidateCallbackResult::Invalid(format!("Rating volatility must be in (0, {}]", MAX_VOLATILITY)));
+    }
+    None
+}
+
+// Validate creation of a Rating entry: a player's unrated starting point.
+pub fn validate_create_rating(
+    action: &SignedActionHashed,
+    rating: Rating,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Only the player can create their own rating
+    if rating.player != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Rating can only be created by the player it belongs to".to_string()));
+    }
+    // 2. A new rating has no games behind it; games are added through updates
+    if rating.games_played != 0 || rating.last_result.is_some() {
+        return Ok(ValidateCallbackResult::Invalid("A new Rating must start with no rated games".to_string()));
+    }
+    // 3. Everyone starts from the Glicko-2 defaults, once
+    if Glicko2Rating::from(&rating) != Glicko2Rating::default() {
+        return Ok(ValidateCallbackResult::Invalid("A new Rating must start at the default rating, deviation and volatility".to_string()));
+    }
+    let earlier_ratings: Vec<Rating> = earlier_chain_entries(action, UnitEntryTypes::Rating)?;
+    if !earlier_ratings.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("A player can only create one Rating".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate updating a Rating: exactly one co-finished game is added per update, and the
+// new values must be the Glicko-2 step from the pre-game ratings both players signed.
+pub fn validate_update_rating(
+    action: &SignedActionHashed,
+    updated_rating: Rating,
+    original_rating: &Rating,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Only the player can update their own rating
+    if original_rating.player != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Rating can only be updated by the player it belongs to".to_string()));
+    }
+    if updated_rating.player != original_rating.player {
+        return Ok(ValidateCallbackResult::Invalid("Cannot change the player of a Rating".to_string()));
+    }
+    // 2. One game per update
+    if updated_rating.games_played != original_rating.games_played + 1 {
+        return Ok(ValidateCallbackResult::Invalid("Each Rating update must add exactly one game".to_string()));
+    }
+    // 3. The game must be backed by a result both players signed, with this player in it
+    let Some(co_signed) = &updated_rating.last_result else {
+        return Ok(ValidateCallbackResult::Invalid("Rating update must reference the co-signed match result it is based on".to_string()));
+    };
+    let result = &co_signed.result;
+    let (my_rating_hash, opponent, opponent_rating_hash) = if result.player_1 == updated_rating.player {
+        (&result.player_1_rating, &result.player_2, &result.player_2_rating)
+    } else if result.player_2 == updated_rating.player {
+        (&result.player_2_rating, &result.player_1, &result.player_1_rating)
+    } else {
+        return Ok(ValidateCallbackResult::Invalid("Rating update must be based on a game the player took part in".to_string()));
+    };
+    let signatures_check = validate_co_signed_result(co_signed)?;
+    if signatures_check != ValidateCallbackResult::Valid {
+        return Ok(signatures_check);
+    }
+    let game_check = validate_result_references_game(co_signed)?;
+    if game_check != ValidateCallbackResult::Valid {
+        return Ok(game_check);
+    }
+
+    // 4. Each game is rated once, and revisions form a single line: no earlier revision on the
+    //    player's chain may have rated this game or be further along than the one being updated.
+    let earlier_ratings: Vec<Rating> = earlier_chain_entries(action, UnitEntryTypes::Rating)?;
+    if earlier_ratings.iter().any(|earlier| {
+        earlier.last_result.as_ref().map(|r| &r.result.game_id) == Some(&result.game_id)
+    }) {
+        return Ok(ValidateCallbackResult::Invalid("This game has already been rated for the player".to_string()));
+    }
+    if earlier_ratings.iter().any(|earlier| earlier.games_played > original_rating.games_played) {
+        return Ok(ValidateCallbackResult::Invalid("Rating update must build on the player's latest Rating revision".to_string()));
+    }
+
+    // 5. The update must start from the player's pre-game rating named in the signed result
+    let Action::Update(update) = action.action() else {
+        return Ok(ValidateCallbackResult::Invalid("Rating update must be an Update action".to_string()));
+    };
+    if update.original_action_address != *my_rating_hash {
+        return Ok(ValidateCallbackResult::Invalid("Rating update must revise the pre-game rating named in the match result".to_string()));
+    }
+
+    // 6. The opponent's pre-game rating is the revision named in the signed result
+    let opponent_record = must_get_valid_record(opponent_rating_hash.clone())?;
+    let opponent_rating = match opponent_record.entry().to_app_option::<Rating>() {
+        Ok(Some(rating)) if rating.player == *opponent => rating,
+        _ => return Ok(ValidateCallbackResult::Invalid("Match result does not name a Rating of the opponent".to_string())),
+    };
+
+    // 7. Recompute the Glicko-2 step and compare
+    let score = match &result.winner {
+        Some(winner) if *winner == updated_rating.player => GameOutcome::WIN,
+        Some(_) => GameOutcome::LOSS,
+        None => GameOutcome::DRAW,
+    };
+    let expected = glicko2::rate_game(Glicko2Rating::from(original_rating), Glicko2Rating::from(&opponent_rating), score);
+    let matches = |submitted: f64, recomputed: f64| (submitted - recomputed).abs() <= RECOMPUTE_TOLERANCE;
+    if !(matches(updated_rating.rating, expected.rating)
+        && matches(updated_rating.deviation, expected.deviation)
+        && matches(updated_rating.volatility, expected.volatility))
+    {
+        return Ok(ValidateCallbackResult::Invalid("Rating update does not match the Glicko-2 result for this game".to_string()));
+    }
+    if let Some(invalid) = check_bounds(&updated_rating) {
+        return Ok(invalid);
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Ratings are never deleted, so a bad run cannot be wiped from history.
+pub fn validate_delete_rating(
+    _action: &SignedActionHashed,
+    _original_rating: Rating,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Rating entries cannot be deleted".to_string()))
+}
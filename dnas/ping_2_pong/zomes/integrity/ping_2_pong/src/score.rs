@@ -1,5 +1,6 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/score.rs
 use hdk::prelude::*;
+use crate::match_result::CoSignedMatchResult;
 
 // Score entry, recorded at the end of a game for one player.
 #[hdk_entry_helper]
@@ -11,4 +12,5 @@ pub struct Score {
     pub created_at: Timestamp, // When the score was recorded
                              // pub opponent_points: u32, // Optional: Could store opponent's score too
                              // pub game_outcome: GameOutcome, // Optional: Win/Loss/Draw enum?
+    pub result: CoSignedMatchResult, // Result both players signed; player_points must agree with it
 }
\ No newline at end of file
@@ -3,6 +3,9 @@ use hdk::prelude::*;
 // Remove Game/GameStatus imports as they are no longer checked here
 // use crate::{score::Score, game::{Game, GameStatus}};
 use crate::score::Score; // Keep Score import
+use crate::UnitEntryTypes;
+use crate::utils::earlier_chain_entries;
+use crate::match_result_validation::{validate_co_signed_result, validate_result_references_game};
 use std::ops::{Add, Sub};
 
 
@@ -27,14 +30,42 @@ pub fn validate_create_score(
     if score.player != game.player_1 && game.player_2.as_ref() != Some(&score.player) { ... } <-- REMOVED
     */
 
-    // 3. REMOVED: Author Check (Optional).
-    //    If we wanted to enforce that only the player records their score,
-    //    this check is simple and could remain. However, the comment indicated
-    //    allowing anyone, so we keep it removed. Coordinator enforces who calls.
-    /*
+    // 3. Check Author: each player records their own score.
     let author = action.action().author();
-    if score.player != *author { ... }
-    */
+    if score.player != *author {
+        return Ok(ValidateCallbackResult::Invalid("Only the player can record their own score".to_string()));
+    }
+
+    // 3a. Check Uniqueness: one Score per (game, player). Since the author is the player,
+    //     an earlier Score for this game would be on the author's own source chain.
+    let earlier_scores: Vec<Score> = earlier_chain_entries(action, UnitEntryTypes::Score)?;
+    if earlier_scores.iter().any(|earlier| earlier.game_id == score.game_id) {
+        return Ok(ValidateCallbackResult::Invalid("A score has already been recorded for this player and game".to_string()));
+    }
+
+    // 3b. Check Co-signed Result: the score must be backed by a result both players signed.
+    let result = &score.result.result;
+    if result.game_id != score.game_id {
+        return Ok(ValidateCallbackResult::Invalid("Score game_id does not match its match result".to_string()));
+    }
+    let expected_points = if score.player == result.player_1 {
+        result.score1
+    } else if score.player == result.player_2 {
+        result.score2
+    } else {
+        return Ok(ValidateCallbackResult::Invalid("Score player did not take part in the match result".to_string()));
+    };
+    if score.player_points != expected_points {
+        return Ok(ValidateCallbackResult::Invalid("Score player_points do not match the co-signed match result".to_string()));
+    }
+    let signatures_check = validate_co_signed_result(&score.result)?;
+    if signatures_check != ValidateCallbackResult::Valid {
+        return Ok(signatures_check);
+    }
+    let game_check = validate_result_references_game(&score.result)?;
+    if game_check != ValidateCallbackResult::Valid {
+        return Ok(game_check);
+    }
 
     // 4. Check Score Sanity: Points within reasonable limits.
     //    Keep this check as it only concerns the Score entry itself.
@@ -63,9 +94,22 @@ pub fn validate_create_score(
     Ok(ValidateCallbackResult::Valid)
 }
 
-// REMOVE validate_update_score and validate_delete_score functions entirely
-// as Scores are intended to be immutable after creation in this design.
+// Scores are immutable after creation: they record a final game result.
+pub fn validate_update_score(
+    _action: &SignedActionHashed,
+    _updated_score: Score,
+    _original_score: &Score,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Score entries cannot be updated".to_string()))
+}
+
+// Scores are never deleted, so the leaderboard history cannot be rewritten.
+pub fn validate_delete_score(
+    _action: &SignedActionHashed,
+    _original_score: Score,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Score entries cannot be deleted".to_string()))
+}
 
 // --- REMOVED Helper Function ---
 // fn get_latest_game_record(original_game_hash: &ActionHash) -> ExternResult<Option<Record>> { ... } // <-- REMOVED
-
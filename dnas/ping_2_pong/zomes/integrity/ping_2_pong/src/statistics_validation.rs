@@ -72,7 +72,32 @@ pub fn validate_create_statistics(
     Ok(ValidateCallbackResult::Valid)
 }
 
-// REMOVE validate_update_statistics and validate_delete_statistics functions entirely.
+// Statistics are immutable once recorded.
+pub fn validate_update_statistics(
+    _action: &SignedActionHashed,
+    _updated_statistics: Statistics,
+    _original_statistics: &Statistics,
+) -> ExternResult<ValidateCallbackResult> {
+    Ok(ValidateCallbackResult::Invalid("Statistics entries cannot be updated".to_string()))
+}
+
+// Only the agent who recorded the statistics may delete them.
+pub fn validate_delete_statistics(
+    action: &SignedActionHashed,
+    _original_statistics: Statistics,
+) -> ExternResult<ValidateCallbackResult> {
+    // The original author is not carried in the entry; compare against the original create action.
+    let Action::Delete(delete) = action.action() else {
+        return Ok(ValidateCallbackResult::Invalid("Expected a Delete action".to_string()));
+    };
+    let original_record = must_get_valid_record(delete.deletes_address.clone())?;
+    if original_record.action().author() != action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Statistics can only be deleted by the agent who recorded them".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
 
 // --- REMOVED Helper Function ---
-// fn get_latest_game_record(original_game_hash: &ActionHash) -> ExternResult<Option<Record>> { ... } // <-- REMOVED
\ No newline at end of file
+// fn get_latest_game_record(original_game_hash: &ActionHash) -> ExternResult<Option<Record>> { ... } // <-- REMOVED
@@ -0,0 +1,213 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/tournament.rs
+use hdk::prelude::*;
+
+// Bounds on the field of a tournament (a 64-player bracket has six rounds).
+pub const MIN_TOURNAMENT_PLAYERS: u32 = 2;
+pub const MAX_TOURNAMENT_PLAYERS: u32 = 64;
+pub const MAX_TOURNAMENT_NAME_LENGTH: usize = 64;
+
+// How matches are arranged. Only single elimination exists today.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub enum TournamentFormat {
+    #[default]
+    SingleElimination, // Winners advance, losers are out; byes fill the first round up to a power of two
+}
+
+// How registered players are ordered into the bracket when the tournament starts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TournamentSeeding {
+    WinRate, // Best win rate first, so the strongest players meet last
+    Random,
+}
+
+// Tournament lifecycle:
+//
+//   Registration -> InProgress (organiser starts it, fixing the seeds)
+//   InProgress   -> Finished   (the final is decided)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TournamentStatus {
+    Registration, // Players register with TournamentToPlayers links
+    InProgress,   // Bracket games are being played
+    Finished,     // `winner` won the final
+}
+
+// A tournament entry. Bracket matches are ordinary Game entries carrying the
+// tournament's id and linked from it with TournamentToGames (see coordinator tournament.rs).
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Tournament {
+    pub name: String,
+    pub organiser: AgentPubKey, // Author of the tournament; starts it and creates the first round
+    pub max_players: u32,
+    pub format: TournamentFormat,
+    pub start_time: Timestamp, // Registration is open until the organiser starts it, not before this
+    pub created_at: Timestamp,
+    pub status: TournamentStatus,
+    // Registered players in seed order (best seed first); set when the tournament starts.
+    pub seeds: Vec<AgentPubKey>,
+    // Set when the tournament finishes: the champion and the Game action that decided the final.
+    pub winner: Option<AgentPubKey>,
+    pub final_game: Option<ActionHash>,
+    // Set with `winner`: the Game action that decided each match the champion won, first
+    // round first (a first-round bye has none), ending with `final_game`.
+    #[serde(default)]
+    pub winning_games: Vec<ActionHash>,
+}
+
+// --- Bracket layout ---
+//
+// Seeds are placed into a bracket of `bracket_size` slots; round 0 pairs them as in
+// `seed_order`, and the winners of matches 2k and 2k+1 of a round meet in match k of the
+// next. A seed position beyond the field is a bye. Validation and the coordinator both
+// derive the bracket from these, so they always agree on who could meet whom.
+
+// Number of first-round places: the field rounded up to a power of two.
+pub fn bracket_size(players: usize) -> usize {
+    players.next_power_of_two()
+}
+
+// Rounds needed to decide a field, the final being the last.
+pub fn bracket_rounds(players: usize) -> u32 {
+    bracket_size(players).trailing_zeros()
+}
+
+// Seed positions (0-based) of the first round, top of the bracket first, for a
+// bracket of `size` (a power of two). Pairs are (best, worst) seed of each quarter,
+// e.g. 8 -> [0,7, 3,4, 1,6, 2,5], so the top two seeds can only meet in the final.
+pub fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let len = order.len();
+        order = order.iter().flat_map(|&seed| [seed, 2 * len - 1 - seed]).collect();
+    }
+    order
+}
+
+// Seed positions `seed` can meet in `round`: those placed in the half of the round's
+// match opposite to `seed` (byes included, they are positions >= the field).
+// Returns None if `seed` is not in a bracket of `size` or the round does not exist.
+pub fn round_opponent_seeds(seed: usize, size: usize, round: u32) -> Option<Vec<usize>> {
+    if round >= size.trailing_zeros() {
+        return None;
+    }
+    let order = seed_order(size);
+    let place = order.iter().position(|&s| s == seed)?;
+    // Places in a round-`round` match come in blocks of 2^(round+1); the opposite half
+    // of our block is the subtree whose winner we meet
+    let half = 1usize << round;
+    let opposite_start = ((place / half) ^ 1) * half;
+    Some(order[opposite_start..opposite_start + half].to_vec())
+}
+
+// Tag of a TournamentToGames link: where the linked game sits in the bracket.
+// Round 0 is the first round; slot counts matches from the top of the bracket.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct TournamentMatchTag {
+    pub round: u32,
+    pub slot: u32,
+}
+
+impl TournamentMatchTag {
+    pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = SerializedBytes::try_from(self.clone()).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(LinkTag::new(bytes.bytes().clone()))
+    }
+
+    pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+        TournamentMatchTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.clone().into_inner())))
+            .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_order_pairs_best_with_worst() {
+        assert_eq!(seed_order(1), vec![0]);
+        assert_eq!(seed_order(2), vec![0, 1]);
+        assert_eq!(seed_order(4), vec![0, 3, 1, 2]);
+        assert_eq!(seed_order(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+    }
+
+    #[test]
+    fn seed_order_places_every_seed_once() {
+        for size in [1, 2, 4, 8, 16, 32, 64] {
+            let mut order = seed_order(size);
+            order.sort_unstable();
+            assert_eq!(order, (0..size).collect::<Vec<_>>(), "size {}", size);
+        }
+    }
+
+    #[test]
+    fn first_round_pairs_sum_to_size_minus_one() {
+        for size in [2, 4, 8, 16, 32, 64] {
+            let order = seed_order(size);
+            for pair in order.chunks(2) {
+                assert_eq!(pair[0] + pair[1], size - 1, "size {}", size);
+            }
+        }
+    }
+
+    #[test]
+    fn byes_go_to_the_best_seeds() {
+        for players in 2..=MAX_TOURNAMENT_PLAYERS as usize {
+            let size = bracket_size(players);
+            let mut byes: Vec<usize> = seed_order(size)
+                .chunks(2)
+                .filter(|pair| pair[1] >= players)
+                .map(|pair| pair[0])
+                .collect();
+            byes.sort_unstable();
+            // Every first-round match has a player, and the byes are the top seeds
+            assert_eq!(byes, (0..size - players).collect::<Vec<_>>(), "{} players", players);
+        }
+    }
+
+    #[test]
+    fn bracket_rounds_fit_the_field() {
+        assert_eq!(bracket_rounds(2), 1);
+        assert_eq!(bracket_rounds(3), 2);
+        assert_eq!(bracket_rounds(8), 3);
+        assert_eq!(bracket_rounds(9), 4);
+        assert_eq!(bracket_rounds(MAX_TOURNAMENT_PLAYERS as usize), 6);
+    }
+
+    #[test]
+    fn opponents_of_a_round_are_the_opposite_subtree() {
+        // 8 places: [0,7, 3,4, 1,6, 2,5]
+        assert_eq!(round_opponent_seeds(0, 8, 0), Some(vec![7]));
+        assert_eq!(round_opponent_seeds(0, 8, 1), Some(vec![3, 4]));
+        assert_eq!(round_opponent_seeds(0, 8, 2), Some(vec![1, 6, 2, 5]));
+        assert_eq!(round_opponent_seeds(5, 8, 0), Some(vec![2]));
+        assert_eq!(round_opponent_seeds(5, 8, 1), Some(vec![1, 6]));
+        assert_eq!(round_opponent_seeds(0, 8, 3), None);
+        assert_eq!(round_opponent_seeds(8, 8, 0), None);
+    }
+
+    #[test]
+    fn top_two_seeds_only_meet_in_the_final() {
+        for size in [2, 4, 8, 16, 32, 64] {
+            let rounds = bracket_rounds(size);
+            for round in 0..rounds {
+                let meets = round_opponent_seeds(0, size, round).unwrap().contains(&1);
+                assert_eq!(meets, round == rounds - 1, "size {} round {}", size, round);
+            }
+        }
+    }
+
+    #[test]
+    fn every_seed_meets_each_other_seed_in_exactly_one_round() {
+        for size in [2, 4, 8, 16, 32, 64] {
+            for seed in 0..size {
+                let mut met: Vec<usize> = (0..bracket_rounds(size))
+                    .flat_map(|round| round_opponent_seeds(seed, size, round).unwrap())
+                    .collect();
+                met.sort_unstable();
+                let others: Vec<usize> = (0..size).filter(|&s| s != seed).collect();
+                assert_eq!(met, others, "size {} seed {}", size, seed);
+            }
+        }
+    }
+}
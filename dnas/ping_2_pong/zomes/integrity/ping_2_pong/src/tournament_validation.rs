@@ -0,0 +1,275 @@
+// ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/tournament_validation.rs
+use hdk::prelude::*;
+use crate::tournament::{
+    Tournament, TournamentStatus, TournamentMatchTag, bracket_size, bracket_rounds, round_opponent_seeds,
+    MIN_TOURNAMENT_PLAYERS, MAX_TOURNAMENT_PLAYERS, MAX_TOURNAMENT_NAME_LENGTH,
+};
+use crate::game::{Game, GameStatus};
+
+// Validate creation of a Tournament entry: it opens for registration with no seeds or result.
+pub fn validate_create_tournament(
+    action: &SignedActionHashed,
+    tournament: Tournament,
+) -> ExternResult<ValidateCallbackResult> {
+    // 1. Check Author
+    if tournament.organiser != *action.action().author() {
+        return Ok(ValidateCallbackResult::Invalid("Tournament organiser must be the author".to_string()));
+    }
+
+    // 2. Check Settings
+    let name_length = tournament.name.trim().chars().count();
+    if name_length == 0 || name_length > MAX_TOURNAMENT_NAME_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!("Tournament name must be 1 to {} characters", MAX_TOURNAMENT_NAME_LENGTH)));
+    }
+    if tournament.max_players < MIN_TOURNAMENT_PLAYERS || tournament.max_players > MAX_TOURNAMENT_PLAYERS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Tournament must allow between {} and {} players", MIN_TOURNAMENT_PLAYERS, MAX_TOURNAMENT_PLAYERS
+        )));
+    }
+
+    // 3. Check Initial State
+    if tournament.status != TournamentStatus::Registration {
+        return Ok(ValidateCallbackResult::Invalid("Tournament must be created open for registration".to_string()));
+    }
+    if !tournament.seeds.is_empty() || tournament.winner.is_some() || tournament.final_game.is_some() || !tournament.winning_games.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Tournament cannot be created with seeds or a result".to_string()));
+    }
+
+    // 4. Check Timestamps: created close to the action, starting no earlier than creation
+    let created_ms = tournament.created_at.as_millis();
+    if (created_ms - action.action().timestamp().as_millis()).abs() > 300_000 {
+        return Ok(ValidateCallbackResult::Invalid("Tournament created_at is too far from the action timestamp".to_string()));
+    }
+    if tournament.start_time < tournament.created_at {
+        return Ok(ValidateCallbackResult::Invalid("Tournament cannot start before it is created".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate updating a Tournament. Only the two lifecycle transitions are allowed:
+//
+//   Registration -> InProgress  (organiser, at or after start_time, fixes the seeds)
+//   InProgress   -> Finished    (organiser or the champion, listing the games the champion won)
+pub fn validate_update_tournament(
+    action: &SignedActionHashed,
+    updated_tournament: Tournament,
+    original_tournament: &Tournament,
+) -> ExternResult<ValidateCallbackResult> {
+    let author = action.action().author();
+    let Action::Update(update) = action.action() else {
+        return Ok(ValidateCallbackResult::Invalid("Tournament update must be an Update action".to_string()));
+    };
+    let tournament_id = update.original_action_address.clone();
+
+    match (&original_tournament.status, &updated_tournament.status) {
+        (TournamentStatus::Registration, TournamentStatus::InProgress) => {
+            // 1. Only the status and seeds change
+            let unchanged = Tournament {
+                status: updated_tournament.status.clone(),
+                seeds: updated_tournament.seeds.clone(),
+                ..original_tournament.clone()
+            };
+            if updated_tournament != unchanged {
+                return Ok(ValidateCallbackResult::Invalid("Starting a tournament can only set its status and seeds".to_string()));
+            }
+            if *author != original_tournament.organiser {
+                return Ok(ValidateCallbackResult::Invalid("Only the organiser can start a tournament".to_string()));
+            }
+            if action.action().timestamp() < original_tournament.start_time {
+                return Ok(ValidateCallbackResult::Invalid("Tournament cannot start before its start time".to_string()));
+            }
+            // 2. Check Seeds: a valid field of distinct players
+            let seeds = &updated_tournament.seeds;
+            if (seeds.len() as u32) < MIN_TOURNAMENT_PLAYERS || seeds.len() as u32 > original_tournament.max_players {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Tournament needs between {} and {} players to start", MIN_TOURNAMENT_PLAYERS, original_tournament.max_players
+                )));
+            }
+            if seeds.iter().enumerate().any(|(i, seed)| seeds[..i].contains(seed)) {
+                return Ok(ValidateCallbackResult::Invalid("A player can only be seeded once".to_string()));
+            }
+            // Note: Registration links cannot be read deterministically here, the coordinator seeds registered players only
+        },
+        (TournamentStatus::InProgress, TournamentStatus::Finished) => {
+            // 1. Only the result changes
+            let unchanged = Tournament {
+                status: updated_tournament.status.clone(),
+                winner: updated_tournament.winner.clone(),
+                final_game: updated_tournament.final_game.clone(),
+                winning_games: updated_tournament.winning_games.clone(),
+                ..original_tournament.clone()
+            };
+            if updated_tournament != unchanged {
+                return Ok(ValidateCallbackResult::Invalid("Finishing a tournament can only set its status and result".to_string()));
+            }
+            let (Some(winner), Some(final_game)) = (&updated_tournament.winner, &updated_tournament.final_game) else {
+                return Ok(ValidateCallbackResult::Invalid("Finished tournament must name its winner and final game".to_string()));
+            };
+            if *author != original_tournament.organiser && author != winner {
+                return Ok(ValidateCallbackResult::Invalid("Only the organiser or the winner can finish a tournament".to_string()));
+            }
+            if !original_tournament.seeds.contains(winner) {
+                return Ok(ValidateCallbackResult::Invalid("Tournament winner must be one of its players".to_string()));
+            }
+            if updated_tournament.winning_games.last() != Some(final_game) {
+                return Ok(ValidateCallbackResult::Invalid("Tournament final_game must be the last of its winning games".to_string()));
+            }
+            // 2. Check the winner won a match in every round, the final included
+            return validate_winning_games(&tournament_id, &original_tournament.seeds, winner, &updated_tournament.winning_games);
+        },
+        (TournamentStatus::Finished, _) => {
+            return Ok(ValidateCallbackResult::Invalid("A finished tournament cannot be updated".to_string()));
+        },
+        (from, to) => {
+            return Ok(ValidateCallbackResult::Invalid(format!("Invalid tournament status transition from {:?} to {:?}", from, to)));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// The champion must have won every round of the bracket: one Game action per round they
+// played (a first-round bye needs none), first round first, each against a player from
+// the part of the bracket they meet in that round (see `round_opponent_seeds`). Those
+// parts are disjoint, so no game can count for two rounds and the last one is the final.
+fn validate_winning_games(tournament_id: &ActionHash, seeds: &[AgentPubKey], winner: &AgentPubKey, winning_games: &[ActionHash]) -> ExternResult<ValidateCallbackResult> {
+    let Some(seed) = seeds.iter().position(|s| s == winner) else {
+        return Ok(ValidateCallbackResult::Invalid("Tournament winner must be one of its players".to_string()));
+    };
+    let size = bracket_size(seeds.len());
+    let opponents_in = |round: u32| -> Vec<AgentPubKey> {
+        round_opponent_seeds(seed, size, round)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|opponent| seeds.get(opponent).cloned()) // Positions beyond the field are byes
+            .collect()
+    };
+    let first_round = if opponents_in(0).is_empty() { 1 } else { 0 };
+    let rounds = bracket_rounds(seeds.len());
+    if winning_games.len() as u32 != rounds - first_round {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Tournament winner must list one won game for each of the {} rounds they played", rounds - first_round
+        )));
+    }
+    for (round, game_action) in (first_round..rounds).zip(winning_games) {
+        let result = validate_won_game(tournament_id, winner, game_action, &opponents_in(round))?;
+        if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// A Game action that decided a match of this tournament in `winner`'s favour against one
+// of `opponents`: finished with them as the co-signed winner, or walked out of by the
+// opponent (Abandoned or Canceled by the other player).
+fn validate_won_game(tournament_id: &ActionHash, winner: &AgentPubKey, game_action: &ActionHash, opponents: &[AgentPubKey]) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(game_action.clone())?;
+    let Some(game) = record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("Tournament winning game is not a Game entry".to_string()));
+    };
+    if game.tournament_id.as_ref() != Some(tournament_id) {
+        return Ok(ValidateCallbackResult::Invalid("Tournament winning game belongs to a different tournament".to_string()));
+    }
+    let opponent = if game.player_1 == *winner {
+        game.player_2.as_ref()
+    } else if game.player_2.as_ref() == Some(winner) {
+        Some(&game.player_1)
+    } else {
+        return Ok(ValidateCallbackResult::Invalid("Tournament winner did not play a listed winning game".to_string()));
+    };
+    if !opponent.is_some_and(|opponent| opponents.contains(opponent)) {
+        return Ok(ValidateCallbackResult::Invalid("Tournament winning game is not against an opponent of that round".to_string()));
+    }
+    let won = match game.game_status {
+        GameStatus::Finished => game.result.as_ref().and_then(|r| r.result.winner.as_ref()) == Some(winner),
+        GameStatus::Abandoned | GameStatus::Canceled => record.action().author() != winner,
+        _ => false,
+    };
+    if !won {
+        return Ok(ValidateCallbackResult::Invalid("Tournament winning game was not won by the winner".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Only the organiser can delete a tournament, and only before it starts.
+pub fn validate_delete_tournament(
+    action: &SignedActionHashed,
+    original_tournament: Tournament,
+) -> ExternResult<ValidateCallbackResult> {
+    if *action.action().author() != original_tournament.organiser {
+        return Ok(ValidateCallbackResult::Invalid("Only the organiser can delete a tournament".to_string()));
+    }
+    if original_tournament.status != TournamentStatus::Registration {
+        return Ok(ValidateCallbackResult::Invalid("Only tournaments open for registration can be deleted".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// A Game carrying a tournament_id is a bracket match: it may be created (and linked
+// from its players) by the tournament organiser as well as by its own players.
+pub fn validate_tournament_game_author(author: &AgentPubKey, game: &Game) -> ExternResult<ValidateCallbackResult> {
+    let Some(tournament_id) = &game.tournament_id else {
+        return Ok(ValidateCallbackResult::Invalid("Game is not part of a tournament".to_string()));
+    };
+    let tournament = must_get_tournament(tournament_id)?;
+    let Some(tournament) = tournament else {
+        return Ok(ValidateCallbackResult::Invalid("Game tournament_id does not point to a Tournament entry".to_string()));
+    };
+    if tournament.organiser != *author && game.player_1 != *author && game.player_2.as_ref() != Some(author) {
+        return Ok(ValidateCallbackResult::Invalid("Tournament games are created by the organiser or their players".to_string()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn must_get_tournament(tournament_id: &ActionHash) -> ExternResult<Option<Tournament>> {
+    must_get_valid_record(tournament_id.clone())?
+        .entry()
+        .to_app_option::<Tournament>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))
+}
+
+// Validate a TournamentToPlayers link: from a Tournament to an agent registering for it.
+pub fn validate_tournament_to_players_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    // Base Check: Must be a Tournament ActionHash
+    let Some(tournament_id) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToPlayers base must be a Tournament ActionHash".into()));
+    };
+    if must_get_tournament(&tournament_id)?.is_none() {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToPlayers base is not a Tournament entry".into()));
+    }
+    // Target Check: Must be an AgentPubKey
+    let Some(player) = create_link.target_address.clone().into_agent_pub_key() else {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToPlayers target must be an AgentPubKey".into()));
+    };
+    // Author Check: Agents can only register themselves
+    if create_link.author != player {
+        return Ok(ValidateCallbackResult::Invalid("Agents can only register themselves for a tournament".into()));
+    }
+    // Note: Registration closing is only known from the latest tournament state, which the coordinator checks
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Validate a TournamentToGames link: from a Tournament to one of its bracket games,
+// tagged with the game's place in the bracket.
+pub fn validate_tournament_to_games_link(create_link: &CreateLink) -> ExternResult<ValidateCallbackResult> {
+    if TournamentMatchTag::from_link_tag(&create_link.tag).is_err() {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToGames tag must be a TournamentMatchTag".into()));
+    }
+    // Base Check: Must be an ActionHash (the tournament, checked against the game below)
+    let Some(tournament_id) = create_link.base_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToGames base must be a Tournament ActionHash".into()));
+    };
+    // Target Check: Must be a Game of this tournament
+    let Some(game_hash) = create_link.target_address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToGames target must be a Game ActionHash".into()));
+    };
+    let game_record = must_get_valid_record(game_hash)?;
+    let Some(game) = game_record.entry().to_app_option::<Game>().map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))? else {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToGames target is not a Game entry".into()));
+    };
+    if game.tournament_id.as_ref() != Some(&tournament_id) {
+        return Ok(ValidateCallbackResult::Invalid("TournamentToGames target game belongs to a different tournament".into()));
+    }
+    // Author Check: Whoever may create the game may link it
+    validate_tournament_game_author(&create_link.author, &game)
+}
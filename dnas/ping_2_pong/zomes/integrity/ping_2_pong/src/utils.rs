@@ -1,7 +1,7 @@
 // ping_2_pong/dnas/ping_2_pong/zomes/integrity/ping_2_pong/src/utils.rs
 use hdk::prelude::*;
 // Import the necessary types from the main lib.rs
-use crate::{AnchorPath, EntryTypes};
+use crate::{AnchorPath, EntryTypes, UnitEntryTypes};
 
 // Function to create a deterministic anchor hash from a string using Path entry creation.
 // Used for global anchors ("games", "presence") and dynamic ones (player names).
@@ -25,4 +25,62 @@ pub fn anchor_for(input: &str) -> ExternResult<AnyLinkableHash> {
 
     // 4. Return the path's entry hash wrapped in AnyLinkableHash
     Ok(AnyLinkableHash::from(path_hash))
-}
\ No newline at end of file
+}
+
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+// Day number (days since the Unix epoch, UTC) of a timestamp.
+pub fn day_of(timestamp: Timestamp) -> i64 {
+    timestamp.as_micros().div_euclid(MICROS_PER_DAY)
+}
+
+// Formats a day number as YYYY-MM-DD (proleptic Gregorian calendar).
+pub fn format_day(day: i64) -> String {
+    // Civil-from-days conversion (H. Hinnant)
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Name of the daily bucket anchor games are linked from, e.g. "games.2026-10-17".
+pub fn game_bucket_name(day: i64) -> String {
+    format!("games.{}", format_day(day))
+}
+
+// Entries of one type committed (created or updated) by the author of `action` earlier
+// on their source chain. Walks back to genesis, so it is only meant for entry types
+// an agent commits about once per game.
+pub fn earlier_chain_entries<T>(action: &SignedActionHashed, entry_type: UnitEntryTypes) -> ExternResult<Vec<T>>
+where
+    T: TryFrom<Entry, Error = WasmError>,
+{
+    let Some(prev_action) = action.action().prev_action() else {
+        return Ok(vec![]); // Nothing before this action
+    };
+    let entry_def = ScopedEntryDefIndex::try_from(entry_type)?;
+    let activity = must_get_agent_activity(
+        action.action().author().clone(),
+        ChainFilter::new(prev_action.clone()),
+    )?;
+    let mut entries = Vec::new();
+    for item in activity {
+        let (entry_type, entry_hash) = match item.action.action() {
+            Action::Create(create) => (&create.entry_type, &create.entry_hash),
+            Action::Update(update) => (&update.entry_type, &update.entry_hash),
+            _ => continue,
+        };
+        if !matches!(entry_type, EntryType::App(def) if def.zome_index == entry_def.zome_index && def.entry_index == entry_def.zome_type) {
+            continue;
+        }
+        entries.push(T::try_from(must_get_entry(entry_hash.clone())?.content)?);
+    }
+    Ok(entries)
+}
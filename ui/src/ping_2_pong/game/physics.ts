@@ -0,0 +1,155 @@
+// Deterministic Pong simulation, a line-for-line port of the coordinator's physics.rs.
+//
+// Positions and velocities are 16.16 fixed-point integers, so Player 1's game, the zome's
+// `verify_ball_state` and replays all produce identical results from the same paddle inputs.
+// Keep this file in step with physics.rs: any difference shows up as a diverging ball.
+// Field names match the Rust structs so states can be passed to zome calls as they are.
+
+const FRAC_BITS = 16;
+const ONE = 1 << FRAC_BITS;
+
+/** Converts whole pixels to fixed-point. */
+export const toFixed = (px: number): number => px << FRAC_BITS;
+
+/** Converts fixed-point back to whole pixels (rounds towards negative infinity). */
+export const toPixels = (value: number): number => value >> FRAC_BITS;
+
+// Multiplies two fixed-point values. The product fits in a double exactly (< 2^53),
+// so flooring the division matches Rust's arithmetic shift of the i64 product.
+const mul = (a: number, b: number): number => Math.floor((a * b) / ONE);
+
+// --- Game constants (pixels unless noted), as in physics.rs ---
+export const CANVAS_WIDTH = 800;
+export const CANVAS_HEIGHT = 600;
+export const PADDLE_WIDTH = 10;
+export const PADDLE_HEIGHT = 100;
+export const BALL_RADIUS = 10;
+export const SERVE_SPEED = 5; // pixels per tick on both axes
+export const MAX_BALL_SPEED = 30; // pixels per tick, keeps the ball from tunnelling through paddles
+export const WINNING_SCORE = 10;
+
+const W = toFixed(CANVAS_WIDTH);
+const H = toFixed(CANVAS_HEIGHT);
+const PW = toFixed(PADDLE_WIDTH);
+const PH = toFixed(PADDLE_HEIGHT);
+const R = toFixed(BALL_RADIUS);
+const SPEEDUP = 68_813; // 1.05 in 16.16
+const ANGLE_FACTOR = 22_938; // 0.35 in 16.16
+
+export type PlayerSide = "Player1" | "Player2";
+
+/** Ball position and velocity in fixed-point. */
+export interface BallState { x: number; y: number; dx: number; dy: number; }
+
+/** Paddle positions (top edge, whole pixels) applied for one tick. */
+export interface PaddleInputs { paddle_1_y: number; paddle_2_y: number; }
+
+/** Full simulation state at a given tick. */
+export interface PhysicsState {
+  tick: number;
+  ball: BallState;
+  paddle_1_y: number; // fixed-point
+  paddle_2_y: number; // fixed-point
+  score1: number;
+  score2: number;
+}
+
+/** What happened during a single step (serde's encoding of physics.rs `StepEvent`). */
+export type StepEvent =
+  | "None"
+  | "WallBounce"
+  | { PaddleHit: PlayerSide }
+  | { Goal: PlayerSide } // The side that scored; the ball has been re-served
+  | { GameOver: PlayerSide }; // The side that won; the simulation stops advancing the ball
+
+/** Initial state of a match, matching the default positions written by `create_game`. */
+export function newGame(firstServeToward: PlayerSide): PhysicsState {
+  const paddleY = toFixed(CANVAS_HEIGHT / 2 - PADDLE_HEIGHT / 2);
+  const state: PhysicsState = {
+    tick: 0,
+    ball: { x: W / 2, y: H / 2, dx: 0, dy: 0 },
+    paddle_1_y: paddleY,
+    paddle_2_y: paddleY,
+    score1: 0,
+    score2: 0,
+  };
+  serve(state, firstServeToward);
+  return state;
+}
+
+/** The winning side, once either player has reached `WINNING_SCORE`. */
+export function winner(state: PhysicsState): PlayerSide | null {
+  if (state.score1 >= WINNING_SCORE) return "Player1";
+  if (state.score2 >= WINNING_SCORE) return "Player2";
+  return null;
+}
+
+// Resets the ball to the centre. The vertical direction alternates with the
+// number of points played so serves are deterministic.
+function serve(state: PhysicsState, toward: PlayerSide) {
+  const dx = toward === "Player1" ? -toFixed(SERVE_SPEED) : toFixed(SERVE_SPEED);
+  const dy = (state.score1 + state.score2) % 2 === 0 ? toFixed(SERVE_SPEED) : -toFixed(SERVE_SPEED);
+  state.ball = { x: W / 2, y: H / 2, dx, dy };
+}
+
+// Clamps a paddle's top edge to the canvas and converts it to fixed-point.
+function paddleToFixed(paddleY: number): number {
+  return toFixed(Math.min(paddleY, CANVAS_HEIGHT - PADDLE_HEIGHT));
+}
+
+function clampSpeed(v: number): number {
+  return Math.max(-toFixed(MAX_BALL_SPEED), Math.min(toFixed(MAX_BALL_SPEED), v));
+}
+
+/** Advances the simulation by one tick using the given paddle inputs. */
+export function step(state: PhysicsState, inputs: PaddleInputs): StepEvent {
+  state.tick += 1;
+  state.paddle_1_y = paddleToFixed(inputs.paddle_1_y);
+  state.paddle_2_y = paddleToFixed(inputs.paddle_2_y);
+
+  const won = winner(state);
+  if (won) return { GameOver: won };
+
+  let event: StepEvent = "None";
+  const p1 = state.paddle_1_y;
+  const p2 = state.paddle_2_y;
+  const ball = state.ball;
+
+  // Move ball
+  ball.x += ball.dx;
+  ball.y += ball.dy;
+
+  // Top/bottom walls
+  if (ball.y + R > H || ball.y - R < 0) {
+    ball.dy = -ball.dy;
+    ball.y = Math.max(R, Math.min(H - R, ball.y));
+    event = "WallBounce";
+  }
+
+  // Paddles
+  if (ball.dx < 0 && ball.x - R < PW && ball.x > R && ball.y > p1 && ball.y < p1 + PH) {
+    ball.dx = clampSpeed(-mul(ball.dx, SPEEDUP));
+    ball.x = PW + R;
+    ball.dy = clampSpeed(mul(ball.y - (p1 + PH / 2), ANGLE_FACTOR));
+    return { PaddleHit: "Player1" };
+  } else if (ball.dx > 0 && ball.x + R > W - PW && ball.x < W - R && ball.y > p2 && ball.y < p2 + PH) {
+    ball.dx = clampSpeed(-mul(ball.dx, SPEEDUP));
+    ball.x = W - PW - R;
+    ball.dy = clampSpeed(mul(ball.y - (p2 + PH / 2), ANGLE_FACTOR));
+    return { PaddleHit: "Player2" };
+  }
+
+  // Goals: the ball left the table past a paddle
+  let scorer: PlayerSide;
+  if (ball.x + R < 0) scorer = "Player2";
+  else if (ball.x - R > W) scorer = "Player1";
+  else return event;
+
+  if (scorer === "Player1") state.score1 += 1;
+  else state.score2 += 1;
+  const gameWinner = winner(state);
+  if (gameWinner) return { GameOver: gameWinner };
+  // Serve towards the player who conceded
+  serve(state, scorer === "Player1" ? "Player2" : "Player1");
+  return { Goal: scorer };
+}
@@ -12,6 +12,7 @@ export interface GlobalChatMessageSignal {
   timestamp: HdkTimestamp; // Milliseconds since epoch
   sender: AgentPubKeyB64;
   content: string;
+  action_hash?: string; // Base64 ChatMessage action hash (absent for messages from older peers)
 }
 
 // You might also want to define the payload separately if it's used elsewhere
@@ -37,3 +38,143 @@ export interface Player {
   player_name: string;
   player_key: AgentPubKey; // Raw AgentPubKey (Uint8Array) as it's stored in the entry
 }
+
+// From the coordinator Signal::GameInvitation (see invitations.rs)
+import type { ActionHash } from '@holochain/client';
+
+export interface GameInvitationSignal {
+  type: "GameInvitation";
+  invitation_hash: ActionHash; // Invitation entry; accept/decline by this hash
+  game_id: ActionHash;
+  inviter: AgentPubKey;
+  message: string;
+  expires_at: number; // Microseconds since epoch
+}
+
+// From ping_2_pong_integrity/src/tournament.rs (struct Tournament)
+export type TournamentStatus = "Registration" | "InProgress" | "Finished";
+
+export interface Tournament {
+  name: string;
+  organiser: AgentPubKey;
+  max_players: number;
+  format: "SingleElimination";
+  start_time: number; // Microseconds since epoch
+  created_at: number; // Microseconds since epoch
+  status: TournamentStatus;
+  seeds: AgentPubKey[]; // Best seed first; empty until the tournament starts
+  winner: AgentPubKey | null;
+  final_game: ActionHash | null;
+  winning_games: ActionHash[]; // Games the winner won, first round first; empty until finished
+}
+
+// From the coordinator tournament.rs (get_tournament_bracket)
+export interface BracketMatch {
+  round: number;
+  slot: number;
+  player_1: AgentPubKey | null;
+  player_2: AgentPubKey | null; // null in the first round means a bye
+  game_id: ActionHash | null;
+  winner: AgentPubKey | null;
+  decided_by: ActionHash | null;
+}
+
+export interface TournamentBracket {
+  tournament_id: ActionHash;
+  tournament: Tournament;
+  rounds: BracketMatch[][];
+}
+
+// From ping_2_pong_integrity/src/league.rs (struct Season)
+export interface Season {
+  name: string;
+  created_by: AgentPubKey;
+  starts_at: number; // Microseconds since epoch
+  ends_at: number;   // Microseconds since epoch, exclusive
+  created_at: number;
+}
+
+// From ping_2_pong_integrity/src/league.rs (struct League)
+export interface League {
+  name: string;
+  organiser: AgentPubKey;
+  season_id: ActionHash;
+  members: AgentPubKey[];
+  created_at: number;
+}
+
+// From the coordinator league.rs (get_league_schedule)
+export interface LeagueFixture {
+  round: number;
+  index: number;
+  player_1: AgentPubKey;
+  player_2: AgentPubKey;
+  game_id: ActionHash | null;
+  status: "Waiting" | "InProgress" | "Finished" | "Abandoned" | "Canceled" | null;
+  result: { score1: number; score2: number; winner: AgentPubKey | null } | null;
+}
+
+// From the coordinator league.rs (get_league_standings)
+export interface LeagueStanding {
+  player: AgentPubKey;
+  played: number;
+  wins: number;
+  losses: number;
+  draws: number;
+  points_for: number;
+  points_against: number;
+  point_differential: number;
+}
+
+// From ping_2_pong_integrity/src/match_series.rs (struct MatchSeries)
+export interface MatchSeries {
+  player_1: AgentPubKey; // The challenger
+  player_2: AgentPubKey;
+  best_of: number;
+  created_at: number; // Microseconds since epoch
+  status: "InProgress" | "Finished";
+  winner: AgentPubKey | null;
+  decided_by: ActionHash | null;
+  wins: { game_number: number; decided_by: ActionHash }[]; // The winner's wins; empty until finished
+}
+
+// From the coordinator match_series.rs (get_series)
+export interface SeriesGame {
+  game_number: number;
+  game_id: ActionHash;
+  player_1: AgentPubKey;
+  player_2: AgentPubKey;
+  status: "Waiting" | "InProgress" | "Finished" | "Abandoned" | "Canceled";
+  winner: AgentPubKey | null;
+  decided_by: ActionHash | null;
+}
+
+export interface SeriesState {
+  series_id: ActionHash;
+  series: MatchSeries;
+  games: SeriesGame[];
+  player_1_wins: number;
+  player_2_wins: number;
+}
+
+// From the coordinator Signal::SeriesUpdate / Signal::SeriesOver
+export interface SeriesUpdateSignal {
+  type: "SeriesUpdate";
+  series_id: ActionHash;
+  player_1: AgentPubKey;
+  player_2: AgentPubKey;
+  player_1_wins: number;
+  player_2_wins: number;
+  game_number: number; // Next game, 0-based
+  next_game_id: ActionHash | null;
+}
+
+export interface SeriesOverSignal {
+  type: "SeriesOver";
+  series_id: ActionHash;
+  player_1: AgentPubKey;
+  player_2: AgentPubKey;
+  winner: AgentPubKey;
+  player_1_wins: number;
+  player_2_wins: number;
+}
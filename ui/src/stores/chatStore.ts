@@ -7,6 +7,10 @@ export const globalChatMessages = writable<GlobalChatMessageSignal[]>([]);
 
 export function addChatMessage(newMessage: GlobalChatMessageSignal) {
     globalChatMessages.update(messages => {
+        // Skip messages already loaded from history
+        if (newMessage.action_hash && messages.some(m => m.action_hash === newMessage.action_hash)) {
+            return messages;
+        }
         const updatedMessages = [...messages, newMessage];
         if (updatedMessages.length > MAX_CHAT_MESSAGES) {
             // Remove the oldest message(s) to maintain the cap
@@ -19,6 +23,21 @@ export function addChatMessage(newMessage: GlobalChatMessageSignal) {
     // globalChatMessages.subscribe(value => console.log('[chatStore] Current messages:', value))();
 }
 
+// Merges messages loaded with get_chat_history, keeping the store sorted by time
+export function mergeChatHistory(history: GlobalChatMessageSignal[]) {
+    globalChatMessages.update(messages => {
+        const known = new Set(messages.map(m => m.action_hash).filter(Boolean));
+        const merged = [...messages, ...history.filter(m => !m.action_hash || !known.has(m.action_hash))]
+            .sort((a, b) => a.timestamp - b.timestamp);
+        return merged.slice(Math.max(0, merged.length - MAX_CHAT_MESSAGES));
+    });
+}
+
+// Removes a message its sender deleted
+export function removeChatMessage(actionHashB64: string) {
+    globalChatMessages.update(messages => messages.filter(m => m.action_hash !== actionHashB64));
+}
+
 // Optional: Function to clear chat messages if needed
 export function clearChatMessages() {
     globalChatMessages.set([]);
@@ -0,0 +1,39 @@
+// ping2pong/ui/src/stores/directMessageStore.ts
+// Direct message conversations, keyed by the other player's Base64 public key.
+import { writable } from "svelte/store";
+
+export interface DirectMessageLine {
+    peer: string;      // Base64 AgentPubKey of the other player
+    fromMe: boolean;
+    content: string;
+    timestamp: number; // Milliseconds since epoch
+    status: 'delivered' | 'stored' | 'received'; // stored: waiting in the recipient's inbox
+    inboxHash?: string; // Base64 action hash of a message that went through the inbox
+}
+
+const MAX_MESSAGES_PER_PEER = 100;
+
+export const directMessages = writable<{ [peer: string]: DirectMessageLine[] }>({});
+// Conversation shown in the DirectMessages panel (set by e.g. the lobby's Message button)
+export const activeDmPeer = writable<string | null>(null);
+
+export function addDirectMessage(line: DirectMessageLine) {
+    directMessages.update(conversations => {
+        const lines = conversations[line.peer] ?? [];
+        if (line.inboxHash && lines.some(l => l.inboxHash === line.inboxHash)) return conversations;
+        const updated = [...lines, line].sort((a, b) => a.timestamp - b.timestamp).slice(-MAX_MESSAGES_PER_PEER);
+        return { ...conversations, [line.peer]: updated };
+    });
+}
+
+// The recipient read a message we left in their inbox
+export function markDelivered(peer: string, inboxHash: string) {
+    directMessages.update(conversations => {
+        const lines = conversations[peer];
+        if (!lines) return conversations;
+        return {
+            ...conversations,
+            [peer]: lines.map(l => (l.inboxHash === inboxHash ? { ...l, status: 'delivered' as const } : l)),
+        };
+    });
+}
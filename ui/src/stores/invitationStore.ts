@@ -14,8 +14,8 @@
         // });
         invitations.update(currentInvitations => {
             const exists = currentInvitations.some(inv =>
-                // Compare game IDs using Base64 strings for reliable comparison
-                encodeHashToBase64(inv.game_id) === encodeHashToBase64(newInvitation.game_id)
+                // Compare invitation hashes using Base64 strings for reliable comparison
+                encodeHashToBase64(inv.invitation_hash) === encodeHashToBase64(newInvitation.invitation_hash)
             );
             if (!exists) {
                 // console.log("[invitationStore] Invitation does not exist, adding.");
@@ -27,13 +27,13 @@
     }
 
     // Helper function to remove an invitation (e.g., after accepting/declining)
-    export function removeInvitation(gameIdToRemove: Uint8Array) { // Accept ActionHash (Uint8Array)
-        const gameIdB64 = encodeHashToBase64(gameIdToRemove);
-        // console.log("[invitationStore] Attempting to remove invitation with gameId:", gameIdB64);
+    export function removeInvitation(invitationHashToRemove: Uint8Array) { // Accept ActionHash (Uint8Array)
+        const invitationHashB64 = encodeHashToBase64(invitationHashToRemove);
+        // console.log("[invitationStore] Attempting to remove invitation:", invitationHashB64);
         invitations.update(currentInvitations => {
             const initialLength = currentInvitations.length;
             const filtered = currentInvitations.filter(inv =>
-                encodeHashToBase64(inv.game_id) !== gameIdB64
+                encodeHashToBase64(inv.invitation_hash) !== invitationHashB64
             );
             if (filtered.length < initialLength) {
                 // console.log("[invitationStore] Invitation removed.");
@@ -43,4 +43,10 @@
             return filtered;
         });
     }
-    
\ No newline at end of file
+
+    // Drops invitations whose expires_at (microseconds) has passed; they can no longer be accepted
+    export function removeExpiredInvitations(nowMs: number = Date.now()) {
+        invitations.update(currentInvitations =>
+            currentInvitations.filter(inv => Number(inv.expires_at) / 1000 > nowMs)
+        );
+    }
@@ -0,0 +1,46 @@
+// ping2pong/ui/src/stores/presenceStore.ts
+// Presence view built from PresenceHeartbeat/PresenceLeft signals (gossip presence mode).
+// In DHT presence mode this stays empty and the lobby relies on get_online_users alone.
+import { writable } from "svelte/store";
+import { encodeHashToBase64, type AgentPubKey } from "@holochain/client";
+
+export type OnlineStatus = 'Online' | 'Idle' | 'InGame' | 'Away'; // Mirrors the integrity zome's OnlineStatus
+export type PresenceMode = 'dht' | 'gossip'; // Mirrors the integrity zome's PresenceMode
+
+export interface PeerPresence {
+    agent: AgentPubKey;
+    status: OnlineStatus;
+    lastSeen: number; // Milliseconds since epoch
+    left?: boolean; // Set by PresenceLeft; hides older DHT presence for this agent
+}
+
+export const AWAY_AFTER_MS = 30_000; // Same thresholds as the coordinator's get_online_users
+export const OFFLINE_AFTER_MS = 10 * 60 * 1000;
+
+// Keyed by the agent's Base64 public key
+export const peerPresence = writable<{ [agentB64: string]: PeerPresence }>({});
+
+export function recordHeartbeat(agent: AgentPubKey, status: OnlineStatus, timestamp: number) {
+    const agentB64 = encodeHashToBase64(agent);
+    peerPresence.update(peers => {
+        const known = peers[agentB64];
+        if (known && known.lastSeen >= timestamp) return peers; // Out-of-order heartbeat
+        return { ...peers, [agentB64]: { agent, status, lastSeen: timestamp } };
+    });
+}
+
+export function recordLeft(agent: AgentPubKey) {
+    const agentB64 = encodeHashToBase64(agent);
+    peerPresence.update(peers => ({
+        ...peers,
+        [agentB64]: { agent, status: 'Away', lastSeen: Date.now(), left: true },
+    }));
+}
+
+// Status as of `now`: Away after a missed heartbeat, null once the peer left or counts as offline
+export function currentStatus(peer: PeerPresence, now: number = Date.now()): OnlineStatus | null {
+    if (peer.left) return null;
+    const age = now - peer.lastSeen;
+    if (age > OFFLINE_AFTER_MS) return null;
+    return age > AWAY_AFTER_MS ? 'Away' : peer.status;
+}
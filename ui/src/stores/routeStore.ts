@@ -1,5 +1,5 @@
 import { writable } from "svelte/store";
 
 // Define a simple store for the current route.
-// Possible routes: "dashboard", "gameplay", "statistics"
+// Possible routes: "dashboard", "gameplay", "spectating", "replay", "statistics"
 export const currentRoute = writable("dashboard");
\ No newline at end of file
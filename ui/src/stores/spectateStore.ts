@@ -0,0 +1,10 @@
+// ping2pong/ui/src/stores/spectateStore.ts
+import { writable } from "svelte/store";
+import type { ActionHash } from "@holochain/client";
+
+// ORIGINAL ActionHash of the game the user is watching (route "spectating"), kept
+// apart from currentGame so game signals meant for players are not applied to it.
+export const spectatingGame = writable<ActionHash | null>(null);
+
+// ORIGINAL ActionHash of the finished game whose replay is being watched (route "replay").
+export const replayingGame = writable<ActionHash | null>(null);